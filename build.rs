@@ -0,0 +1,159 @@
+// generates the remasters' goal table (src/alcatraz_utils/goals.csv) as rust, after checking it for mistakes that would otherwise only show up
+// as miscounted goals at runtime.  the totals for each tour and level are worked out here too, so the splitters don't need magic numbers
+
+use std::{collections::HashMap, env, fmt::Write, fs, path::Path};
+
+const GOAL_FILE: &str = "src/alcatraz_utils/goals.csv";
+
+// the size of the career table: 4 games, up to 10 levels each, up to 15 goal slots per level
+const TOUR_COUNT: usize = 4;
+const LEVEL_COUNT: usize = 10;
+const GOALS_PER_LEVEL: usize = 15;
+
+const GOAL_TYPES: [&str; 4] = ["Normal", "Medal", "GoldMedal", "Pro"];
+
+#[derive(Clone, Copy, Default)]
+struct Totals {
+    levels: u32,
+    goals: u32,
+    medals: u32,
+    gold_medals: u32,
+    pro_goals: u32,
+}
+
+impl Totals {
+    fn add(&mut self, ty: &str) {
+        match ty {
+            "Normal" => self.goals += 1,
+            "Medal" => self.medals += 1,
+            "GoldMedal" => {
+                self.medals += 1;
+                self.gold_medals += 1;
+            },
+            "Pro" => self.pro_goals += 1,
+            _ => {},
+        }
+    }
+
+    fn to_rust(&self) -> String {
+        format!("GoalTotals {{ levels: {}, goals: {}, medals: {}, gold_medals: {}, pro_goals: {} }}", self.levels, self.goals, self.medals, self.gold_medals, self.pro_goals)
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", GOAL_FILE);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let text = fs::read_to_string(GOAL_FILE).unwrap_or_else(|e| panic!("couldn't read {}: {}", GOAL_FILE, e));
+
+    let mut errors = Vec::new();
+    let mut goals = Vec::new();
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    let mut slots: HashMap<(usize, usize, usize), (&str, usize)> = HashMap::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with("name,") {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [name, tour, level, idx, ty] = fields[..] else {
+            errors.push(format!("line {}: expected 5 fields (name,tour,level,index,type), found {}", line_number, fields.len()));
+            continue;
+        };
+
+        let (Ok(tour), Ok(level), Ok(idx)) = (tour.parse::<usize>(), level.parse::<usize>(), idx.parse::<usize>()) else {
+            errors.push(format!("line {}: tour, level and index have to be numbers", line_number));
+            continue;
+        };
+
+        if tour >= TOUR_COUNT || level >= LEVEL_COUNT || idx >= GOALS_PER_LEVEL {
+            errors.push(format!("line {}: {} is out of range at ({}, {}, {}); tours go up to {}, levels to {} and goals to {}", line_number, name, tour, level, idx, TOUR_COUNT - 1, LEVEL_COUNT - 1, GOALS_PER_LEVEL - 1));
+            continue;
+        }
+
+        if !GOAL_TYPES.contains(&ty) {
+            errors.push(format!("line {}: unknown goal type {} (expected one of {})", line_number, ty, GOAL_TYPES.join(", ")));
+            continue;
+        }
+
+        if let Some(first) = names.insert(name, line_number) {
+            errors.push(format!("line {}: {} is already in the table on line {}", line_number, name, first));
+            continue;
+        }
+
+        // alternate versions of a goal share a slot, but they have to agree on what kind of goal it is
+        match slots.get(&(tour, level, idx)) {
+            Some(&(other_ty, other_line)) if other_ty != ty => {
+                errors.push(format!("line {}: {} is a {} goal, but shares its slot with a {} goal on line {}", line_number, name, ty, other_ty, other_line));
+                continue;
+            },
+            Some(_) => {},
+            None => {
+                slots.insert((tour, level, idx), (ty, line_number));
+            },
+        }
+
+        goals.push((name, tour, level, idx, ty));
+    }
+
+    if !errors.is_empty() {
+        panic!("{} has {} problem(s):\n{}", GOAL_FILE, errors.len(), errors.join("\n"));
+    }
+
+    // totals count slots rather than names, since that's how the career table counts completed goals
+    let mut level_totals = [[Totals::default(); LEVEL_COUNT]; TOUR_COUNT];
+    for (&(tour, level, _), &(ty, _)) in &slots {
+        level_totals[tour][level].levels = 1;
+        level_totals[tour][level].add(ty);
+    }
+
+    let mut tour_totals = [Totals::default(); TOUR_COUNT];
+    for (tour, levels) in level_totals.iter().enumerate() {
+        for level in levels {
+            tour_totals[tour].levels += level.levels;
+            tour_totals[tour].goals += level.goals;
+            tour_totals[tour].medals += level.medals;
+            tour_totals[tour].gold_medals += level.gold_medals;
+            tour_totals[tour].pro_goals += level.pro_goals;
+        }
+    }
+
+    let mut out = String::new();
+    writeln!(out, "// generated by build.rs from {}", GOAL_FILE).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "pub const TOUR_COUNT: usize = {};", TOUR_COUNT).unwrap();
+    writeln!(out, "pub const LEVEL_COUNT: usize = {};", LEVEL_COUNT).unwrap();
+    writeln!(out, "pub const GOALS_PER_LEVEL: usize = {};", GOALS_PER_LEVEL).unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "const GOAL_LIST: [(&str, (u32, u32, u32, GoalType)); {}] = [", goals.len()).unwrap();
+    for (name, tour, level, idx, ty) in &goals {
+        writeln!(out, "    ({:?}, ({}, {}, {}, GoalType::{})),", name, tour, level, idx, ty).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "pub static TOUR_TOTALS: [GoalTotals; TOUR_COUNT] = [").unwrap();
+    for totals in &tour_totals {
+        writeln!(out, "    {},", totals.to_rust()).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "pub static LEVEL_TOTALS: [[GoalTotals; LEVEL_COUNT]; TOUR_COUNT] = [").unwrap();
+    for levels in &level_totals {
+        writeln!(out, "    [").unwrap();
+        for totals in levels {
+            writeln!(out, "        {},", totals.to_rust()).unwrap();
+        }
+        writeln!(out, "    ],").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("goal_table.rs");
+    fs::write(&path, out).unwrap_or_else(|e| panic!("couldn't write {}: {}", path.display(), e));
+}
@@ -1,14 +1,30 @@
 // utilities for the unreal 4 THPS games (1+2, 3+4)
 
+use std::collections::HashSet;
+
 use asr::{game_engine::unreal as asr_unreal};
-use goal_table::GOAL_TABLE;
+use goal_table::{GoalClassifier, GOALS_PER_LEVEL, LEVEL_COUNT, TOUR_COUNT};
+use run_clock::RunClock;
+
+pub use goal_table::{GoalTotals, LEVEL_TOTALS, TOUR_TOTALS};
+pub use run_clock::RunTimeAccumulator;
 
 mod goal_table;
+mod run_clock;
+
+// how often the goal system pointer is checked against a fresh walk of the object graph, and how often to try finding the offsets again
+// once they've gone stale, in ticks
+const DEEP_CHECK_TICKS: u32 = 600;
+const RESOLVE_RETRY_TICKS: u32 = 120;
 
 pub struct AlcatrazContext {
     unreal_module: asr_unreal::Module,
-    _game: Game,
+    game: Game,
     offsets: Offsets,
+    valid: bool,    // false while the offsets are stale and haven't been found again yet
+    generation: u32,    // counts how many times the offsets have been found, so anything read through the old ones can be thrown out
+    ticks_until_check: u32,
+    run_clock: RunClock,
 }
 
 impl AlcatrazContext {
@@ -21,7 +37,7 @@ impl AlcatrazContext {
         let unreal_module = match asr_unreal::Module::attach(process, unreal_version, main_module_address) {
             Some(v) => v,
             None => {
-                asr::print_message(&format!("Failed to attach to unreal!"));
+                warn!("Failed to attach to unreal!");
 
                 return None
             },
@@ -30,12 +46,67 @@ impl AlcatrazContext {
         let offsets = Offsets::new(process, &unreal_module, game)?;
 
         Some(Self {
-            _game: game,
+            game,
             unreal_module,
             offsets,
+            valid: true,
+            generation: 0,
+            ticks_until_check: DEEP_CHECK_TICKS,
+            run_clock: RunClock::default(),
         })
     }
 
+    // makes sure the goal system we found is still alive, since the local player (and its subsystems) are recreated on things like
+    // returning to the title screen or switching profiles.  if it's gone, everything is found again.  call this once per tick
+    pub fn refresh(&mut self, process: &asr::Process) {
+        self.update_run_clock(process);
+
+        if self.ticks_until_check > 0 {
+            self.ticks_until_check -= 1;
+        }
+
+        if self.valid {
+            // every tick, make sure the object still has the name it had when we found it.  freed memory rarely still reads the same
+            let name = get_uobject_name(process, &self.unreal_module, &self.offsets.goal_system);
+            let mut stale = name != self.offsets.goal_system_name;
+
+            // every so often, walk from the world to the goal system again to catch one that's been replaced by a new object of the same name
+            if !stale && self.ticks_until_check == 0 {
+                self.ticks_until_check = DEEP_CHECK_TICKS;
+                stale = match Offsets::get_goal_system_pointer(process, &self.unreal_module) {
+                    Some(v) => v.get_address() != self.offsets.goal_system.get_address(),
+                    None => true,
+                };
+            }
+
+            if !stale {
+                return;
+            }
+
+            warn!("The goal system has gone stale; finding offsets again...");
+            self.valid = false;
+            self.ticks_until_check = 0;
+        }
+
+        if self.ticks_until_check > 0 {
+            return;
+        }
+
+        match Offsets::new(process, &self.unreal_module, self.game) {
+            Some(v) => {
+                info!("Offsets found!");
+                self.offsets = v;
+                self.valid = true;
+                self.generation = self.generation.wrapping_add(1);
+                self.ticks_until_check = DEEP_CHECK_TICKS;
+                self.list_addresses();
+            },
+            None => {
+                self.ticks_until_check = RESOLVE_RETRY_TICKS;
+            },
+        }
+    }
+
     pub fn get_career_state(&self, process: &asr::Process) -> CareerState {
         CareerState::new(process, self)
     }
@@ -69,6 +140,26 @@ impl AlcatrazContext {
         }
     }
 
+    fn update_run_clock(&mut self, process: &asr::Process) {
+        let slots = if self.valid && self.is_run_active(process) {
+            process.read_pointer_path::<[f32; run_clock::SLOTS]>(self.unreal_module.g_world(), asr::PointerSize::Bit64, &vec![0x0, self.offsets.game_state, self.offsets.subgame_state]).ok()
+        } else {
+            None
+        };
+
+        self.run_clock.update(slots);
+    }
+
+    // seconds into the current run by the game's run clock, as of the last refresh.  0 if there's no run or the clock hasn't been found yet
+    pub fn get_run_time(&self) -> f32 {
+        self.run_clock.run_time()
+    }
+
+    // a run is going and its clock is counting down (not paused, ...)
+    pub fn is_run_clock_running(&self) -> bool {
+        self.run_clock.is_running()
+    }
+
     pub fn get_level_name(&self, process: &asr::Process) -> String {
         let uworld = match self.unreal_module.get_g_world_uobject(process){
             Some(v) => v,
@@ -92,13 +183,21 @@ impl AlcatrazContext {
     }
 
     pub fn list_addresses(&self) {
-        asr::print_message(&format!(""));
-
-        asr::print_message(&format!("GENGINE: {:#018x}", self.unreal_module.g_engine().value()));
-        asr::print_message(&format!("GWORLD: {:#018x}", self.unreal_module.g_world().value()));
+        debug!("GENGINE: {:#018x}", self.unreal_module.g_engine().value());
+        debug!("GWORLD: {:#018x}", self.unreal_module.g_world().value());
         self.offsets.list_offsets();
+    }
+}
 
-        asr::print_message(&format!(""));
+fn get_uobject_name(process: &asr::Process, module: &asr_unreal::Module, object: &asr_unreal::UObject) -> String {
+    match object.get_fname::<128>(process, module) {
+        Ok(v) => {
+            match v.validate_utf8() {
+                Ok(v) => v.to_string(),
+                Err(_) => "".to_string(),
+            }
+        },
+        Err(_) => "".to_string(),
     }
 }
 
@@ -130,6 +229,7 @@ struct Offsets {
     game_state: u64,    // offset from UWorld
     subgame_state: u64, // offset from GameState
     goal_system: asr_unreal::UObject,
+    goal_system_name: String,   // the object's name when it was found, to tell if it's been freed
     skater_name: u64,   // offset from GoalSystem
     career_count: u64,  // offset from GoalSystem
     careers: u64,   // offset from GoalSystem
@@ -161,6 +261,10 @@ impl Offsets {
         let subgame_state = game_state_obj.get_field_offset(process, unreal_module, "SubGameStateRepInfo")? as u64;
 
         let goal_system = Self::get_goal_system_pointer(process, unreal_module)?;
+        let goal_system_name = get_uobject_name(process, unreal_module, &goal_system);
+        if goal_system_name.is_empty() {
+            return None;
+        }
 
         let skater_name = match game {
             Game::THPS12 => 0x130,
@@ -182,6 +286,7 @@ impl Offsets {
             game_state,
             subgame_state,
             goal_system,
+            goal_system_name,
             skater_name,
             career_count,
             careers,
@@ -189,14 +294,14 @@ impl Offsets {
     }
 
     fn list_offsets(&self) {
-        asr::print_message(&format!("OFFSETS:"));
-        asr::print_message(&format!("LOADING: {:#018x}", self.loading));
-        asr::print_message(&format!("GAME STATE: {:#018x}", self.game_state));
-        asr::print_message(&format!("SUBGAME STATE: {:#018x}", self.subgame_state));
-        asr::print_message(&format!("GOAL SYSTEM: {:#018x}", self.goal_system.get_address().value()));
-        asr::print_message(&format!("SKATER NAME: {:#018x}", self.skater_name));
-        asr::print_message(&format!("CAREER COUNT: {:#018x}", self.career_count));
-        asr::print_message(&format!("CAREERS: {:#018x}", self.careers));
+        debug!("OFFSETS:");
+        debug!("LOADING: {:#018x}", self.loading);
+        debug!("GAME STATE: {:#018x}", self.game_state);
+        debug!("SUBGAME STATE: {:#018x}", self.subgame_state);
+        debug!("GOAL SYSTEM: {:#018x} ({})", self.goal_system.get_address().value(), self.goal_system_name);
+        debug!("SKATER NAME: {:#018x}", self.skater_name);
+        debug!("CAREER COUNT: {:#018x}", self.career_count);
+        debug!("CAREERS: {:#018x}", self.careers);
     }
 
     fn get_goal_system_pointer(process: &asr::Process, module: &asr_unreal::Module) -> Option<asr_unreal::UObject> {
@@ -236,7 +341,7 @@ impl Offsets {
             Err(_) => return None,
         };
 
-        //asr::print_message(&format!("Subsystem count: {}", subsystem_count));
+        trace!("Subsystem count: {}", subsystem_count);
 
         let mut local_player_goal_system = None;
 
@@ -252,21 +357,13 @@ impl Offsets {
                 Ok(v) => {
                     let object = asr_unreal::UObject::new(asr::Address::new(v.value()));
 
-                    let name = match object.get_fname::<128>(process, module) {
-                        Ok(v) => {
-                            match v.validate_utf8() {
-                                Ok(v) => v.to_string(),
-                                Err(_) => "".to_string(),
-                            }
-                        },
-                        Err(_) => "".to_string(),
-                    };
+                    let name = get_uobject_name(process, module, &object);
 
-                    //asr::print_message(&format!("    {}: {}", i, name));
+                    trace!("    {}: {}", i, name);
 
                     if name == "LocalPlayerGoalSystem" {
                         local_player_goal_system = Some(object);
-                        //asr::print_message(&format!("FOUND GOAL SYSTEM {}", i));
+                        trace!("Found goal system at subsystem {}", i);
                     }
                 },
                 Err(_) => {},
@@ -292,24 +389,25 @@ impl Offsets {
 }
 
 // THPS1+2/3+4 doesn't store its goals like the other games: it stores each goal non-linearly (maybe they expected to add more?)
-// so we need to construct our own career struct to make it more convenient to both count goals (when AG&G criteria is added) and keep track of medals more easily
+// so we need to construct our own career struct to make it more convenient to both count goals and keep track of medals more easily
+// every skater has their own career in the game, so one is kept for each of them.  the splitters mostly look at the current skater's,
+// but "all skaters" style categories need the others too
 pub struct CareerState {
-    goals: Vec<Vec<Vec<bool>>>,
-    goal_count: u32,
-    tours: Vec<TourState>,
+    careers: Vec<(asr_unreal::FNameKey, SkaterCareer)>,
     skater: Option<asr_unreal::FNameKey>,
+    classifier: GoalClassifier,
+    empty: SkaterCareer,    // stands in for the current skater's career when they don't have one yet
+    generation: u32,    // the context's generation the careers were read with
 }
 
 impl CareerState {
     fn new(process: &asr::Process, context: &AlcatrazContext) -> Self {
-        let goals = vec![vec![vec![false; 15]; 10]; 4];
-        let tours = vec![TourState::default(); 4];
-
         let mut result = Self {
-            goals,
-            goal_count: 0,
-            tours, 
-            skater: None
+            careers: Vec::new(),
+            skater: None,
+            classifier: GoalClassifier::default(),
+            empty: SkaterCareer::new(),
+            generation: context.generation,
         };
 
         result.update(process, context);
@@ -317,134 +415,216 @@ impl CareerState {
         result
     }
 
-    fn reset(&mut self) {
-        for tour in &mut self.goals {
-            for level in tour {
-                level.fill(false);
-            }
+    pub fn update(&mut self, process: &asr::Process, context: &AlcatrazContext) {
+        // hold on to what we have until the goal system has been found again
+        if !context.valid {
+            return;
         }
 
-        self.tours.fill(TourState::default());
+        // the goal system was found again (back to the title screen, a profile switch, ...), so the careers we have may belong to
+        // a profile that isn't loaded anymore.  start over rather than keep their goals around
+        if context.generation != self.generation {
+            debug!("Offsets changed; reading careers again");
 
-        self.goal_count = 0;
-    }
+            self.generation = context.generation;
+            self.careers.clear();
+            self.skater = None;
+        }
 
-    pub fn update(&mut self, process: &asr::Process, context: &AlcatrazContext) {
         let skater_fname = context.get_skater_fname(process);
 
         if skater_fname != self.skater {
-            asr::print_message(&format!("Skater changed, resetting goals"));
-
-            self.reset();
+            debug!("Skater changed");
 
             self.skater = skater_fname;
         }
 
-        // collect all completed goals and apply them to the career goals
-        // go through each career until you find the one for the expected skater
+        // collect all completed goals for every career in the careers array
         let career_count = match process.read_pointer_path::<u32>(context.offsets.goal_system.get_address(), asr::PointerSize::Bit64, &vec![context.offsets.career_count as u64]) {
             Ok(v) => v,
             Err(_) => 0,
         };
 
-        let mut career_offset = -1;
+        let mut found = Vec::new();
         for i in 0..career_count {
             let career_fname = match process.read_pointer_path::<asr_unreal::FNameKey>(context.offsets.goal_system.get_address(), asr::PointerSize::Bit64, &vec![context.offsets.careers, (i * 0x60) as u64]) {
-                Ok(v) => Some(v),
-                Err(_) => None,
+                Ok(v) => v,
+                Err(_) => continue,
             };
 
-            if career_fname == skater_fname {
-                career_offset = i as i32;
-            }
+            let idx = match self.careers.iter().position(|(fname, _)| *fname == career_fname) {
+                Some(v) => v,
+                None => {
+                    debug!("Found career for {}", get_fname_string(process, &context.unreal_module, career_fname));
+                    self.careers.push((career_fname, SkaterCareer::new()));
+                    self.careers.len() - 1
+                },
+            };
+
+            self.careers[idx].1.update(process, context, i, &mut self.classifier);
+            found.push(career_fname);
         }
 
-        if career_offset != -1 {
-            let goal_count = match process.read_pointer_path::<u32>(context.offsets.goal_system.get_address(), asr::PointerSize::Bit64, &vec!(context.offsets.careers, (career_offset as u64 * 0x60) + 0x10 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            };
+        // careers that aren't in the array anymore (a different save was loaded, ...) are dropped, so their goals don't count towards anything
+        self.careers.retain(|(fname, _)| found.contains(fname));
+    }
+
+    fn current(&self) -> &SkaterCareer {
+        match self.skater {
+            Some(skater) => self.get_skater_career(skater).unwrap_or(&self.empty),
+            None => &self.empty,
+        }
+    }
 
-            if goal_count < self.goal_count {
-                asr::print_message(&format!("Goal count lower than previous, resetting goals"));
-                self.reset();
+    fn get_skater_career(&self, skater: asr_unreal::FNameKey) -> Option<&SkaterCareer> {
+        self.careers.iter().find(|(fname, _)| *fname == skater).map(|(_, career)| career)
+    }
+
+    // the current skater's goals
+    pub fn get_goal_state(&self, tour: u32, level: u32, goal: u32) -> bool {
+        self.current().goals[tour as usize][level as usize][goal as usize]
+    }
+
+    pub fn get_goal_count(&self) -> u32 {
+        self.current().goal_count
+    }
+
+    pub fn get_tour_state(&self, tour: u32) -> &TourState {
+        &self.current().tours[tour as usize]
+    }
+
+    // goals completed across every skater's career
+    pub fn get_total_goal_count(&self) -> u32 {
+        self.careers.iter().map(|(_, career)| career.goal_count).sum()
+    }
+
+    // how many skaters have finished a tour, by whatever counts as finishing it for the category
+    pub fn get_skaters_with_completed_tour(&self, tour: u32, is_complete: impl Fn(&TourState) -> bool) -> u32 {
+        self.careers.iter().filter(|(_, career)| is_complete(&career.tours[tour as usize])).count() as u32
+    }
+
+    // a specific skater's progress, or None if they haven't started a career
+    #[allow(dead_code)]     // nothing splits on a single other skater yet
+    pub fn get_skater_tour_state(&self, skater: asr_unreal::FNameKey, tour: u32) -> Option<&TourState> {
+        self.get_skater_career(skater).map(|career| &career.tours[tour as usize])
+    }
+}
+
+// one skater's career, built up from the goals completed in it
+struct SkaterCareer {
+    goals: Vec<Vec<Vec<bool>>>,
+    inferred_goals: HashSet<String>,    // goals that aren't in the goal table, which don't count towards its totals
+    goal_count: u32,
+    tours: Vec<TourState>,
+}
+
+impl SkaterCareer {
+    fn new() -> Self {
+        Self {
+            goals: vec![vec![vec![false; GOALS_PER_LEVEL]; LEVEL_COUNT]; TOUR_COUNT],
+            inferred_goals: HashSet::new(),
+            goal_count: 0,
+            tours: vec![TourState::default(); TOUR_COUNT],
+        }
+    }
+
+    fn reset(&mut self) {
+        for tour in &mut self.goals {
+            for level in tour {
+                level.fill(false);
             }
+        }
 
-            let mut has_invalid_goal = false;
+        self.inferred_goals.clear();
+        self.tours.fill(TourState::default());
 
-            for i in self.goal_count..goal_count {
-                let goal_name = match process.read_pointer_path::<asr_unreal::FNameKey>(context.offsets.goal_system.get_address(), asr::PointerSize::Bit64, &vec!(context.offsets.careers, (career_offset as u64 * 0x60) + 0x8 as u64, (i as u64 * 0x30) + 0x10 as u64)) {
-                    Ok(v) => get_fname_string(process, &context.unreal_module, v),
-                    Err(_) => "".to_string(),
-                };
+        self.goal_count = 0;
+    }
 
-                if let Some((tour, level, idx, ty)) = GOAL_TABLE.get(goal_name.as_str()) {
-                    if !self.goals[*tour as usize][*level as usize][*idx as usize] {
-                        self.goals[*tour as usize][*level as usize][*idx as usize] = true;
-
-                        match ty {
-                            goal_table::GoalType::Normal => {
-                                self.tours[*tour as usize].goals += 1;
-                            },
-                            goal_table::GoalType::Medal => {
-                                self.tours[*tour as usize].medals += 1;
-                            },
-                            goal_table::GoalType::GoldMedal => {
-                                self.tours[*tour as usize].medals += 1;
-                                self.tours[*tour as usize].gold_medals += 1;
-                            },
-                            goal_table::GoalType::Pro => {
-                                self.tours[*tour as usize].pro_goals += 1;
-                            },
-                        }
-                        //asr::print_message(&format!("Goal completed: {}", goal_name));
+    // reads any goals completed since the last update from the career at this index in the careers array
+    fn update(&mut self, process: &asr::Process, context: &AlcatrazContext, career_index: u32, classifier: &mut GoalClassifier) {
+        let goal_count = match process.read_pointer_path::<u32>(context.offsets.goal_system.get_address(), asr::PointerSize::Bit64, &vec!(context.offsets.careers, (career_index as u64 * 0x60) + 0x10 as u64)) {
+            Ok(v) => v,
+            Err(_) => 0,
+        };
+
+        if goal_count < self.goal_count {
+            debug!("Goal count lower than previous, resetting goals");
+            self.reset();
+        }
+
+        if goal_count == self.goal_count {
+            return;
+        }
+
+        let mut has_invalid_goal = false;
+
+        for i in self.goal_count..goal_count {
+            let goal_name = match process.read_pointer_path::<asr_unreal::FNameKey>(context.offsets.goal_system.get_address(), asr::PointerSize::Bit64, &vec!(context.offsets.careers, (career_index as u64 * 0x60) + 0x8 as u64, (i as u64 * 0x30) + 0x10 as u64)) {
+                Ok(v) => get_fname_string(process, &context.unreal_module, v),
+                Err(_) => "".to_string(),
+            };
+
+            if let Some((tour, level, idx, ty)) = classifier.lookup(goal_name.as_str()) {
+                if classifier.is_inferred(goal_name.as_str()) {
+                    // only the table's goals count towards completion, so these are kept track of on their own
+                    if self.inferred_goals.insert(goal_name.clone()) {
+                        self.tours[tour as usize].inferred_goals += 1;
+                        debug!("Goal completed (not in the goal table): {}", goal_name);
                     } else {
-                        //asr::print_message(&format!("DUPLICATE GOAL COMPLETED: {}", goal_name));
-                        // bizarre bug: when a SKATE goal is completed in 3+4, it expands the goal array by two then the second entry is replaced with the next goal completed
-                        // if we see a duplicate, that means that means we see an invalid goal and should not process it
+                        debug!("Duplicate goal completed: {}", goal_name);
                         has_invalid_goal = true;
                     }
+                } else if !self.goals[tour as usize][level as usize][idx as usize] {
+                    self.goals[tour as usize][level as usize][idx as usize] = true;
+
+                    match ty {
+                        goal_table::GoalType::Normal => {
+                            self.tours[tour as usize].goals += 1;
+                        },
+                        goal_table::GoalType::Medal => {
+                            self.tours[tour as usize].medals += 1;
+                        },
+                        goal_table::GoalType::GoldMedal => {
+                            self.tours[tour as usize].medals += 1;
+                            self.tours[tour as usize].gold_medals += 1;
+                        },
+                        goal_table::GoalType::Pro => {
+                            self.tours[tour as usize].pro_goals += 1;
+                        },
+                    }
+                    debug!("Goal completed: {}", goal_name);
                 } else {
-                    asr::print_message(&format!("Unrecognized goal completed: {}", goal_name));
+                    debug!("Duplicate goal completed: {}", goal_name);
+                    // bizarre bug: when a SKATE goal is completed in 3+4, it expands the goal array by two then the second entry is replaced with the next goal completed
+                    // if we see a duplicate, that means that means we see an invalid goal and should not process it
+                    has_invalid_goal = true;
                 }
+            } else {
+                warn!("Unrecognized goal completed: {}", goal_name);
             }
+        }
 
-            self.goal_count = goal_count;
+        self.goal_count = goal_count;
 
-            if has_invalid_goal {
-                self.goal_count -= 1;
-            }
+        if has_invalid_goal {
+            self.goal_count -= 1;
+        }
 
-            for (i, tour) in self.goals.iter().enumerate() {
-                let mut levels_with_goals = 0;
+        for (i, tour) in self.goals.iter().enumerate() {
+            let mut levels_with_goals = 0;
 
-                for level in tour {
-                    for goal in level {
-                        if *goal {
-                            levels_with_goals += 1;
-                            break;
-                        }
+            for level in tour {
+                for goal in level {
+                    if *goal {
+                        levels_with_goals += 1;
+                        break;
                     }
                 }
-
-                self.tours[i].levels_with_goals = levels_with_goals;
             }
-        } else {
-            // career not found.  reset goals
-            self.reset();
-        }
-    }
 
-    pub fn get_goal_state(&self, tour: u32, level: u32, goal: u32) -> bool {
-        self.goals[tour as usize][level as usize][goal as usize]
-    }
-
-    pub fn get_goal_count(&self) -> u32 {
-        self.goal_count
-    }
-
-    pub fn get_tour_state(&self, tour: u32) -> &TourState {
-        &self.tours[tour as usize]
+            self.tours[i].levels_with_goals = levels_with_goals;
+        }
     }
 }
 
@@ -455,4 +635,5 @@ pub struct TourState {
     pub medals: u32,
     pub gold_medals: u32,
     pub levels_with_goals: u32,
+    pub inferred_goals: u32,    // completed goals that aren't in the goal table, of any type
 }
@@ -0,0 +1,210 @@
+// the run clock, which counts down from a run's length.  it isn't a property of its own, but it lives in SubGameStateRepInfo next to the
+// gamemode and run flags, so while a run is going the start of that struct is watched for the one float that counts down with real time.
+// once it's been found, the same slot is read for the rest of the session
+
+use asr::time::Duration;
+
+pub const SLOTS: usize = 32;    // floats watched from the start of SubGameStateRepInfo
+const TICK_RATE: f32 = 120.0;
+const SEARCH_TICKS: u32 = 120;  // how long a slot has to count down with real time before it's taken as the clock
+const MAX_STEP: f32 = 0.5;  // the most the clock can drop in one tick
+const STALL_TICKS: u32 = 12;    // ticks without the clock moving before it counts as stopped.  the game can draw slower than we tick
+
+#[derive(Default)]
+pub struct RunClock {
+    slot: Option<usize>,
+    run_start: Option<[f32; SLOTS]>,    // the highest each slot has read this run, which for the clock is the run's length
+    prev: [f32; SLOTS],
+    search_start: [f32; SLOTS],
+    candidates: u32,    // a bit for each slot that's counted down every tick since search_start
+    search_ticks: u32,
+    ticks_since_moved: u32,
+}
+
+impl RunClock {
+    // call once per tick with the floats at the start of SubGameStateRepInfo, or None when there's no run going
+    pub fn update(&mut self, slots: Option<[f32; SLOTS]>) {
+        let Some(slots) = slots else {
+            if self.run_start.take().is_some() && self.slot.is_none() {
+                warn!("Couldn't find the run clock during that run; \"Sum of run times\" timing didn't count it");
+            }
+
+            self.search_ticks = 0;
+            return;
+        };
+
+        let first_tick = self.run_start.is_none();
+
+        // the clock can still be on the last run's time for a moment when a run starts, so go with the highest it reads
+        let run_start = self.run_start.get_or_insert(slots);
+        for (start, v) in run_start.iter_mut().zip(slots) {
+            if v.is_finite() && (!start.is_finite() || v > *start) {
+                *start = v;
+            }
+        }
+
+        if self.slot.is_none() {
+            self.search(&slots);
+        }
+
+        if let Some(i) = self.slot {
+            self.ticks_since_moved = if !first_tick && slots[i] < self.prev[i] {
+                0
+            } else {
+                self.ticks_since_moved.saturating_add(1)
+            };
+        }
+
+        self.prev = slots;
+    }
+
+    fn search(&mut self, slots: &[f32; SLOTS]) {
+        if self.search_ticks == 0 {
+            self.search_start = *slots;
+            self.candidates = (0..SLOTS).filter(|i| slots[*i].is_finite() && slots[*i] > 0.0).fold(0, |acc, i| acc | 1 << i);
+            self.search_ticks = 1;
+            return;
+        }
+
+        for i in 0..SLOTS {
+            let step = self.prev[i] - slots[i];
+            if !(slots[i].is_finite() && slots[i] > 0.0 && (0.0..MAX_STEP).contains(&step)) {
+                self.candidates &= !(1 << i);
+            }
+        }
+
+        self.search_ticks += 1;
+        if self.search_ticks <= SEARCH_TICKS {
+            return;
+        }
+
+        // anything that's counted down by about as long as we've been watching.  a paused game or a lagging tick rate just means watching again
+        let elapsed = SEARCH_TICKS as f32 / TICK_RATE;
+        let matches: Vec<usize> = (0..SLOTS)
+            .filter(|i| self.candidates & (1 << i) != 0 && (elapsed * 0.75..elapsed * 1.25).contains(&(self.search_start[*i] - slots[*i])))
+            .collect();
+
+        self.search_ticks = 0;
+
+        match matches[..] {
+            [i] => {
+                info!("Found the run clock at SubGameStateRepInfo + {:#x} ({:.2} seconds left)", i * 4, slots[i]);
+                self.slot = Some(i);
+            },
+            _ => debug!("{} values counted down like the run clock; watching again", matches.len()),
+        }
+    }
+
+    // seconds into the current run by the clock.  0 without a run, or before the clock has been found
+    pub fn run_time(&self) -> f32 {
+        match (self.slot, &self.run_start) {
+            (Some(i), Some(run_start)) => (run_start[i] - self.prev[i]).max(0.0),
+            _ => 0.0,
+        }
+    }
+
+    // the clock is counting down, i.e. a run is going and the game isn't paused.  until it's been found, any run counts
+    pub fn is_running(&self) -> bool {
+        self.run_start.is_some() && (self.slot.is_none() || self.ticks_since_moved < STALL_TICKS)
+    }
+}
+
+// "sum of run times" igt, as used by classic-style leaderboards: how long each run lasted by the run clock, added up
+pub struct RunTimeAccumulator {
+    completed: f64,     // seconds, from runs that have ended
+    current: f64,       // seconds into the run in progress
+    prev_igt: Duration,
+}
+
+impl RunTimeAccumulator {
+    pub fn new() -> Self {
+        Self {
+            completed: 0.0,
+            current: 0.0,
+            prev_igt: Duration::seconds(-1),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    // the game time to show, or None if it hasn't changed since the last update
+    pub fn update(&mut self, is_running: bool, run_time: f32) -> Option<Duration> {
+        if is_running {
+            self.current = run_time as f64;
+        } else {
+            // commit the run's time when it ends
+            self.completed += self.current;
+            self.current = 0.0;
+        }
+
+        let igt = Duration::seconds_f64(self.completed + self.current);
+
+        if igt != self.prev_igt {
+            self.prev_igt = igt;
+            Some(igt)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOCK: usize = 5;
+
+    // one tick of a run that started with 120 seconds on the clock, next to a value that goes up and one that sits still
+    fn slots(tick: u32) -> [f32; SLOTS] {
+        let mut result = [0.0; SLOTS];
+        result[CLOCK] = 120.0 - tick as f32 / TICK_RATE;
+        result[1] = tick as f32;
+        result[2] = 60.0;
+        result
+    }
+
+    #[test]
+    fn finds_the_value_that_counts_down_with_real_time() {
+        let mut clock = RunClock::default();
+        for tick in 0..=SEARCH_TICKS {
+            clock.update(Some(slots(tick)));
+        }
+
+        assert_eq!(clock.slot, Some(CLOCK));
+        assert!((clock.run_time() - 1.0).abs() < 0.01);
+        assert!(clock.is_running());
+    }
+
+    #[test]
+    fn keeps_watching_while_the_game_is_paused() {
+        let mut clock = RunClock::default();
+        for tick in 0..=SEARCH_TICKS {
+            clock.update(Some(slots(tick.min(10))));
+        }
+
+        assert_eq!(clock.slot, None);
+    }
+
+    #[test]
+    fn stops_running_when_the_clock_does() {
+        let mut clock = RunClock::default();
+        for tick in 0..=SEARCH_TICKS + STALL_TICKS {
+            clock.update(Some(slots(tick.min(SEARCH_TICKS))));
+        }
+
+        assert!(!clock.is_running());
+
+        clock.update(None);
+        assert_eq!(clock.run_time(), 0.0);
+    }
+
+    #[test]
+    fn adds_up_the_runs() {
+        let mut run_time = RunTimeAccumulator::new();
+        assert_eq!(run_time.update(true, 10.0), Some(Duration::seconds(10)));
+        assert_eq!(run_time.update(false, 0.0), None);
+        assert_eq!(run_time.update(true, 5.0), Some(Duration::seconds(15)));
+    }
+}
@@ -0,0 +1,33 @@
+// helpers for attaching to a game that might not be fully loaded yet.  lookups are retried with an increasing delay instead of giving up,
+// and lib.rs stops the whole thing if the game closes while we're waiting
+
+use asr::{Address, Process};
+
+const FIRST_RETRY_TICKS: u32 = 30;
+const MAX_RETRY_TICKS: u32 = 960;   // 8 seconds at 120 ticks per second
+
+pub async fn retry<T>(what: &str, mut lookup: impl FnMut() -> Option<T>) -> T {
+    let mut wait_ticks = FIRST_RETRY_TICKS;
+
+    loop {
+        if let Some(v) = lookup() {
+            return v;
+        }
+
+        warn!("Failed to find {}! Trying again in {} ticks...", what, wait_ticks);
+
+        for _ in 0..wait_ticks {
+            asr::future::next_tick().await;
+        }
+
+        wait_ticks = (wait_ticks * 2).min(MAX_RETRY_TICKS);
+    }
+}
+
+pub async fn module_address(process: &Process, process_name: &str) -> Address {
+    retry(format!("the {} module", process_name).as_str(), || process.get_module_address(process_name).ok()).await
+}
+
+pub async fn module_range(process: &Process, process_name: &str) -> (Address, u64) {
+    retry(format!("the {} module", process_name).as_str(), || process.get_module_range(process_name).ok()).await
+}
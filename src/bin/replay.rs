@@ -0,0 +1,104 @@
+// replays a recorded state trace through the autosplitter's game logic and prints what the timer would have done
+// build it for the host, since the default target is wasm: cargo run --bin replay --target <host triple> -- <trace>
+
+use std::{env, fs, process::ExitCode};
+
+use asr::time::Duration;
+use thps_autosplitter::{log, settings::{GoalList, Settings}, trace::{self, ReplayTimer, TimerEvent, TraceRecord}};
+
+const USAGE: &str = "usage: replay [--series] [--split-on-game-change] [--segments <count>] [--thaw-goals <list>] [--verbose] <trace file>";
+
+fn format_time(time: Duration) -> String {
+    let millis = time.whole_milliseconds();
+    let sign = if millis < 0 { "-" } else { "" };
+    let millis = millis.abs();
+
+    format!("{}{}:{:02}.{:03}", sign, millis / 60000, (millis / 1000) % 60, millis % 1000)
+}
+
+fn describe(event: &TimerEvent) -> String {
+    match event {
+        TimerEvent::Start => "start".to_string(),
+        TimerEvent::Split => "split".to_string(),
+        TimerEvent::Reset => "reset".to_string(),
+        TimerEvent::PauseGameTime => "pause game time".to_string(),
+        TimerEvent::ResumeGameTime => "resume game time".to_string(),
+        TimerEvent::SetGameTime(time) => format!("set game time {}", format_time(*time)),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut settings = Settings::default();
+    let mut segments = None;
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--series" => settings.series_mode = true,
+            "--split-on-game-change" => settings.split_on_game_change = true,
+            "--thaw-goals" => {
+                settings.thaw_story_goals = match args.next() {
+                    Some(v) => GoalList::parse(&v),
+                    None => {
+                        eprintln!("--thaw-goals needs a list of goals\n{}", USAGE);
+                        return ExitCode::FAILURE;
+                    },
+                };
+            },
+            "--verbose" => settings.log_level = log::Level::Debug,
+            "--segments" => {
+                segments = match args.next().and_then(|v| v.parse().ok()) {
+                    Some(v) => Some(v),
+                    None => {
+                        eprintln!("--segments needs a number\n{}", USAGE);
+                        return ExitCode::FAILURE;
+                    },
+                };
+            },
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Couldn't read {}: {}", path, e);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    // anything that isn't a trace line (other log output, etc.) is skipped
+    let records = contents.lines().filter_map(TraceRecord::parse).collect::<Vec<_>>();
+    if records.is_empty() {
+        eprintln!("No trace records found in {}", path);
+        return ExitCode::FAILURE;
+    }
+
+    // the splitters' log messages go to stderr as the replay runs
+    log::set_max_level(settings.log_level);
+
+    let mut timer = ReplayTimer::new(segments);
+    let result = trace::replay(&records, &mut timer, &settings);
+
+    for (tick, event) in &timer.events {
+        println!("{:>8}  {}", tick, describe(event));
+    }
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        },
+    }
+}
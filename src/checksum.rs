@@ -0,0 +1,109 @@
+// neversoft games refer to names (script fields, goals, levels, ...) by a checksum of the name rather than the string itself
+// the checksum is a crc32 of the lower-cased name with forward slashes turned into backslashes, without the final inversion
+
+const TABLE: [u32; 256] = make_table();
+
+const fn make_table() -> [u32; 256] {
+    let mut table = [0; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut v = i as u32;
+
+        let mut bit = 0;
+        while bit < 8 {
+            v = if v & 1 != 0 { (v >> 1) ^ 0xedb88320 } else { v >> 1 };
+            bit += 1;
+        }
+
+        table[i] = v;
+        i += 1;
+    }
+
+    table
+}
+
+pub const fn checksum(name: &str) -> u32 {
+    let bytes = name.as_bytes();
+    let mut result = 0xffffffff;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = match bytes[i] {
+            b'/' => b'\\',
+            b => b.to_ascii_lowercase(),
+        };
+
+        result = TABLE[((result ^ b as u32) & 0xff) as usize] ^ (result >> 8);
+        i += 1;
+    }
+
+    result
+}
+
+// checksum of a string literal, worked out at compile time
+macro_rules! checksum {
+    ($name:literal) => {{
+        const CHECKSUM: u32 = $crate::checksum::checksum($name);
+        CHECKSUM
+    }};
+}
+
+// names we know the games use, so they can be shown in log messages.  add to this whenever a new name turns up
+static NAMES: &[&str] = &[
+    "career",
+    "classic",
+    "difficulty_levels",
+    "goalmanager_params",
+    "hasBeaten",
+    "hasSeen",
+    "isLocked",
+    "win_record",
+];
+
+// goals we've only ever seen by checksum, so their script names aren't known, but what they are is.  these are the ones the splitters look for
+static GOALS: &[(u32, &str)] = &[
+    // THAW story
+    (0x7a446a0a, "THAW Beverly Hills unlock goal"),
+    (0x7c626a8a, "THAW Boone's ranch goal"),
+    (0xe206ff29, "THAW Dave's ranch goal"),
+    (0x0b655a1c, "THAW Murphy's ranch goal"),
+    (0x8f0cd51c, "THAW falling down goal"),
+    (0x607572ca, "THAW last zen goal"),
+    (0x3a800176, "THAW Amjam goal"),
+    (0xb9415865, "THAW Daewon goal"),
+    (0xea9af00c, "THAW Oil Rig payment"),
+    (0xd32df1bb, "THAW chopper goal"),
+    (0xda77ca92, "THAW Black Widowz goal"),
+    (0x97e3e2cf, "THAW Joey B baggie goal"),
+    (0x82510d90, "THAW taco truck payment"),
+    (0x8c0507e5, "THAW casino goal"),
+    (0x419fe6b0, "THAW casino goal"),
+    (0x9b9cd093, "THAW casino goal"),
+    (0x4829aa9b, "THAW casino goal"),
+    (0x3f2e9a0d, "THAW casino goal"),
+    (0xd120fb21, "THAW casino goal"),
+    (0xd67b166d, "THAW casino goal"),
+    (0x99156422, "THAW final goal"),
+];
+
+pub fn name(checksum: u32) -> Option<&'static str> {
+    NAMES.iter().find(|name| self::checksum(name) == checksum).copied()
+}
+
+// the name if we know it, otherwise what the goal is (if we know that) along with the checksum in hex
+pub fn describe(checksum: u32) -> String {
+    if let Some(v) = name(checksum) {
+        return v.to_string();
+    }
+
+    match GOALS.iter().find(|(goal, _)| *goal == checksum) {
+        Some((_, v)) => format!("{} ({:#010x})", v, checksum),
+        None => format!("{:#010x}", checksum),
+    }
+}
+
+// known values from the games, to make sure the algorithm matches
+const _: () = assert!(checksum("career") == 0x4da4937b);
+const _: () = assert!(checksum("goalmanager_params") == 0x23d4170a);
+const _: () = assert!(checksum("hasBeaten") == 0x49807745);
@@ -1,4 +1,25 @@
-use asr::Process;
+use asr::{Process, settings::Gui};
+
+use settings::Settings;
+use timer::AsrTimer;
+
+#[macro_use]
+pub mod log;
+#[macro_use]
+mod checksum;
+#[macro_use]
+mod scan;
+
+mod attach;
+mod memory;
+mod research;
+mod script;
+pub mod settings;
+mod split_rules;
+pub mod timer;
+pub mod trace;
+mod variables;
+mod watcher;
 
 mod thps2;
 mod thps3;
@@ -12,44 +33,64 @@ mod mhpb;
 
 mod alcatraz_utils;
 
+#[cfg(target_family = "wasm")]
 asr::async_main!(stable);
 
+// only registered as the entry point in the wasm build.  natively (e.g. the replay tool) it's still compiled, so the process handling it uses isn't dead code
+#[cfg_attr(not(target_family = "wasm"), allow(dead_code))]
 async fn main() {
+    let mut settings = Settings::register();
+
     loop {
-        asr::print_message("Looking for process...");
+        info!("Looking for process...");
 
         let (&name, &game, process) = asr::future::retry(|| {
             PROCESS_NAMES.iter().find_map(|(name, game)| Some((name, game, find_process(name, game)?)))
         }).await;
 
+        log::set_prefix(format!("{:?}", game).as_str());
+        settings.refresh();
+
+        let continuing_series = timer::begin_game(&mut AsrTimer, &settings);
+
         process.until_closes(async {
-            asr::print_message(format!("Detected {}", name).as_str());
+            info!("Detected {}", name);
 
             match game {
-                Game::THPS2 => thps2::run(&process, name).await,
-                Game::THPS3 => thps3::run(&process, name).await,
-                Game::THPS4 => thps4::run(&process, name).await,
-                Game::THUG1 => thug1::run(&process, name).await,
-                Game::THUG2 => thug2::run(&process, name).await,
-                Game::THAW => thaw::run(&process, name).await,
-                Game::THPS12 => thps12::run(&process, name).await,
-                Game::THPS34 => thps34::run(&process, name).await,
-                Game::MHPB => mhpb::run(&process, name).await,
+                Game::THPS2 => thps2::run(&process, name, &mut settings, continuing_series).await,
+                Game::THPS3 => thps3::run(&process, name, &mut settings, continuing_series).await,
+                Game::THPS4 => thps4::run(&process, name, &mut settings, continuing_series).await,
+                Game::THUG1 => thug1::run(&process, name, &mut settings, continuing_series).await,
+                Game::THUG2 => thug2::run(&process, name, &mut settings, continuing_series).await,
+                Game::THAW => thaw::run(&process, name, &mut settings, continuing_series).await,
+                Game::THPS12 => thps12::run(&process, name, &mut settings, continuing_series).await,
+                Game::THPS34 => thps34::run(&process, name, &mut settings, continuing_series).await,
+                Game::MHPB => mhpb::run(&process, name, &mut settings, continuing_series).await,
             }
             
             asr::future::next_tick().await;
         }).await;
     
-        asr::print_message("Game Closed");
+        info!("Game Closed");
+        log::set_prefix("");
 
-        if matches!(asr::timer::state(), asr::timer::TimerState::Running) || matches!(asr::timer::state(), asr::timer::TimerState::Paused) {
-            asr::timer::reset();
-        }
+        settings.refresh();
+
+        timer::end_game(&mut AsrTimer, &settings);
 
         asr::future::next_tick().await;
     }
 }
 
+// messages go to the livesplit log when running as an autosplitter, or stderr when the game logic runs natively
+pub fn print_message(message: &str) {
+    #[cfg(target_family = "wasm")]
+    asr::print_message(message);
+
+    #[cfg(not(target_family = "wasm"))]
+    eprintln!("{}", message);
+}
+
 fn find_process(name: &str, game: &Game) -> Option<Process> {
     if matches!(game, Game::THPS12) {
         let processes = Process::list_by_name(name)?;
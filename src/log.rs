@@ -0,0 +1,85 @@
+// leveled logging.  messages are prefixed with the game being run, and anything more detailed than info is hidden unless turned on in the settings
+// use the macros (error!, warn!, info!, debug!, trace!) rather than calling into this module directly, so disabled messages aren't formatted
+
+use std::sync::{Mutex, atomic::{AtomicU8, Ordering}};
+
+use asr::settings::Gui;
+
+#[derive(Gui, Default, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Errors only
+    Error,
+    /// Errors and warnings
+    Warn,
+    /// Normal
+    #[default]
+    Info,
+    /// Debug (extra diagnostics)
+    Debug,
+    /// Trace (very noisy)
+    Trace,
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+static PREFIX: Mutex<String> = Mutex::new(String::new());
+
+pub fn set_max_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+// the game currently being split, shown in front of every message.  empty while looking for a game
+pub fn set_prefix(prefix: &str) {
+    if let Ok(mut v) = PREFIX.lock() {
+        v.clear();
+        v.push_str(prefix);
+    }
+}
+
+pub fn write(level: Level, message: &str) {
+    let prefix = match PREFIX.lock() {
+        Ok(v) if !v.is_empty() => format!("[{}] ", v),
+        _ => String::new(),
+    };
+
+    let tag = match level {
+        Level::Error => "ERROR: ",
+        Level::Warn => "WARNING: ",
+        Level::Info => "",
+        Level::Debug => "DEBUG: ",
+        Level::Trace => "TRACE: ",
+    };
+
+    crate::print_message(format!("{}{}{}", prefix, tag, message).as_str());
+}
+
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format!($($arg)*).as_str());
+        }
+    };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { log!($crate::log::Level::Error, $($arg)*) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => { log!($crate::log::Level::Warn, $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { log!($crate::log::Level::Info, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { log!($crate::log::Level::Debug, $($arg)*) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { log!($crate::log::Level::Trace, $($arg)*) };
+}
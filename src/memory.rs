@@ -0,0 +1,158 @@
+// abstraction over where game memory comes from, so the game logic can run against something other than a live process
+
+#[cfg(test)]
+use std::collections::HashMap;
+
+use asr::{Address, PointerSize, Process};
+use bytemuck::CheckedBitPattern;
+#[cfg(test)]
+use bytemuck::NoUninit;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadError;
+
+pub trait MemorySource {
+    fn read_bytes(&self, address: Address, buf: &mut [u8]) -> Result<(), ReadError>;
+
+    fn read<T: CheckedBitPattern>(&self, address: impl Into<Address>) -> Result<T, ReadError> {
+        let mut buf = vec![0u8; std::mem::size_of::<T>()];
+        self.read_bytes(address.into(), &mut buf)?;
+
+        bytemuck::checked::try_pod_read_unaligned(&buf).map_err(|_| ReadError)
+    }
+
+    // follows the same rules as asr::Process::read_pointer_path: every offset but the last is dereferenced
+    fn read_pointer_path<T: CheckedBitPattern>(&self, address: impl Into<Address>, pointer_size: PointerSize, path: &[u64]) -> Result<T, ReadError> {
+        let mut address = address.into();
+
+        let (&last, path) = path.split_last().ok_or(ReadError)?;
+        for &offset in path {
+            address = self.read_pointer(address + offset, pointer_size)?;
+        }
+
+        self.read(address + last)
+    }
+
+    // reads a whole struct or table at once, so it costs one read instead of one per field
+    fn read_block(&self, address: impl Into<Address>, len: usize) -> Result<Block, ReadError> {
+        let mut bytes = vec![0u8; len];
+        self.read_bytes(address.into(), &mut bytes)?;
+
+        Ok(Block { bytes })
+    }
+
+    fn read_pointer(&self, address: Address, pointer_size: PointerSize) -> Result<Address, ReadError> {
+        match pointer_size {
+            PointerSize::Bit64 => Ok(Address::new(self.read::<u64>(address)?)),
+            PointerSize::Bit32 => Ok(Address::new(self.read::<u32>(address)? as u64)),
+            PointerSize::Bit16 => Ok(Address::new(self.read::<u16>(address)? as u64)),
+        }
+    }
+}
+
+// a copy of a chunk of game memory, with fields decoded from it locally.  the default is empty, so every field reads as None
+#[derive(Default)]
+pub struct Block {
+    bytes: Vec<u8>,
+}
+
+impl Block {
+    // None if the field runs off the end of the block
+    pub fn get<T: CheckedBitPattern>(&self, offset: usize) -> Option<T> {
+        let bytes = self.bytes.get(offset..offset + std::mem::size_of::<T>())?;
+        bytemuck::checked::try_pod_read_unaligned(bytes).ok()
+    }
+}
+
+impl MemorySource for Process {
+    fn read_bytes(&self, address: Address, buf: &mut [u8]) -> Result<(), ReadError> {
+        self.read_into_buf(address, buf).map_err(|_| ReadError)
+    }
+}
+
+// in-memory stand-in for a game process, so the game modules can be tested.  only addresses that have been written can be read
+#[cfg(test)]
+pub struct MockMemory {
+    bytes: HashMap<u64, u8>,
+    next_alloc: u64,
+}
+
+#[cfg(test)]
+impl MockMemory {
+    const HEAP_START: u64 = 0x10000000;
+    const ALLOC_SIZE: u64 = 0x1000;
+
+    pub fn new() -> Self {
+        Self {
+            bytes: HashMap::new(),
+            next_alloc: Self::HEAP_START,
+        }
+    }
+
+    pub fn write_bytes(&mut self, address: impl Into<Address>, data: &[u8]) {
+        let address = address.into().value();
+
+        for (i, b) in data.iter().enumerate() {
+            self.bytes.insert(address + i as u64, *b);
+        }
+    }
+
+    pub fn write<T: NoUninit>(&mut self, address: impl Into<Address>, value: T) {
+        self.write_bytes(address, bytemuck::bytes_of(&value));
+    }
+
+    fn write_pointer(&mut self, address: Address, pointer_size: PointerSize, target: Address) {
+        match pointer_size {
+            PointerSize::Bit64 => self.write(address, target.value()),
+            PointerSize::Bit32 => self.write(address, target.value() as u32),
+            PointerSize::Bit16 => self.write(address, target.value() as u16),
+        }
+    }
+
+    // writes a value at the end of a pointer chain, creating any missing links along the way
+    pub fn write_pointer_path<T: NoUninit>(&mut self, address: impl Into<Address>, pointer_size: PointerSize, path: &[u64], value: T) {
+        let mut address = address.into();
+
+        let Some((&last, path)) = path.split_last() else {
+            return;
+        };
+
+        for &offset in path {
+            address = match self.read_pointer(address + offset, pointer_size) {
+                Ok(v) if !v.is_null() => v,
+                _ => {
+                    let target = self.alloc();
+                    self.write_pointer(address + offset, pointer_size, target);
+                    target
+                },
+            };
+        }
+
+        self.write(address + last, value);
+    }
+
+    fn alloc(&mut self) -> Address {
+        let result = Address::new(self.next_alloc);
+        self.next_alloc += Self::ALLOC_SIZE;
+
+        result
+    }
+}
+
+#[cfg(test)]
+impl Default for MockMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl MemorySource for MockMemory {
+    fn read_bytes(&self, address: Address, buf: &mut [u8]) -> Result<(), ReadError> {
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = *self.bytes.get(&(address.value() + i as u64)).ok_or(ReadError)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,23 @@
 use asr::{Address, Process, timer::TimerState, time::Duration};
 
-struct State {
+use crate::{attach, memory::MemorySource, research::Researcher, settings::{MhpbCategory, Settings}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
+
+#[derive(Copy, Clone)]
+pub struct Offsets {
+    modules: u32,    // the frontend and game are loaded as separate modules, and everything else is read relative to this
+}
+
+static OFFSETS: Offsets = Offsets {
+    modules: 0x139db4,
+};
+
+pub struct State {
     is_timer_running: bool, // got it
     _timer_vblanks: u32,
     level_id: u8,   // got it
     mode: u8,   // got it
     module: Module, // got it
-    _gold_count: u32,
+    gold_count: u32,
     medal_count: u32,
     goal_count: u8,
     is_loading: bool,
@@ -19,28 +30,86 @@ enum Module {
     Game,
 }
 
+impl Module {
+    fn from_id(id: u8) -> Self {
+        match id {
+            1 => Module::Frontend,
+            2 => Module::Game,
+            _ => Module::Unknown,
+        }
+    }
+}
+
+// the module currently loaded, told apart by its size
+fn read_module(process: &impl MemorySource, modules: Address) -> Module {
+    let size = match process.read::<u32>(modules + 0x3c as u32) {
+        Ok(v) => process.read::<u32>(modules + v + 0x1c as u32),
+        Err(e) => Err(e),
+    };
+
+    match size {
+        Ok(v) => {
+            trace!("Module size: {:#x}", v);
+            if v == 0x43000 {
+                Module::Frontend
+            } else if v == 0x9c000 {
+                Module::Game
+            } else {
+                Module::Unknown
+            }
+        },
+        Err(_) => Module::Unknown,
+    }
+}
+
+// goals, golds and medals from a rider's profile, which is read in one go
+fn read_rider_profile(process: &impl MemorySource, address: Address) -> (u8, u8, u32) {
+    match process.read_block(address, 0x38) {
+        Ok(profile) => {
+            let goal_count = profile.get::<u8>(0).unwrap_or(0);
+            let gold_count = profile.get::<u8>(3).unwrap_or(0);
+            let medal_count = [4 + 6, 4 + 7].iter().filter(|i| profile.get::<u8>(**i).is_some_and(|v| v != 0)).count() as u32;
+
+            (goal_count, gold_count, medal_count)
+        },
+        Err(_) => (0, 0, 0),
+    }
+}
+
+pub struct Watchers {
+    modules: MemoryWatcher<u32>,
+    timer_level: MemoryWatcher<u32>,
+
+    // in the game module.  the load states are read directly, since a failed read there means not loading rather than 0
+    is_timer_running: MemoryWatcher<bool>,
+    level_id: MemoryWatcher<u8>,
+    mode: MemoryWatcher<u8>,
+    timer_vblanks: MemoryWatcher<u32>,
+    is_loading: MemoryWatcher<u8>,
+}
+
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
+            modules: MemoryWatcher::new(&[offsets.modules as u64]),
+            timer_level: MemoryWatcher::new(&[0x15e8f0]),
+            is_timer_running: MemoryWatcher::new(&[0xc6438]),
+            level_id: MemoryWatcher::new(&[0x220a93]),
+            mode: MemoryWatcher::new(&[0x220ab0]),
+            timer_vblanks: MemoryWatcher::new(&[0xc61dc]),
+            is_loading: MemoryWatcher::new(&[0xbc6b0]),
+        }
+    }
+}
+
 impl State {
-    pub fn _check_for_reset(process: &Process, base_addr: Address) -> bool {
-        let module = match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x3c as u64)) {
-            Ok(v) => {
-                match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (v + 0x1c) as u64)) {
-                    Ok(v) => {
-                        //asr::print_message(&format!("MODULE SIZE: {}", v));
-                        if v == 0x43000 {
-                            Module::Frontend
-                        } else if v== 0x9c000 {
-                            Module::Game
-                        } else {
-                            Module::Unknown
-                        }
-                    },
-                    Err(_) => Module::Unknown, 
-                }
-            },
-            Err(_) => Module::Unknown, 
+    pub fn _check_for_reset(process: &impl MemorySource, base_addr: Address, offsets: &Offsets) -> bool {
+        let modules = match process.read::<u32>(base_addr + offsets.modules) {
+            Ok(v) => Address::new(v as u64),
+            Err(_) => return false,
         };
 
-        if matches!(module, Module::Frontend) {
+        if matches!(read_module(process, modules), Module::Frontend) {
             for i in 0..9 {
                 let goal_count = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x850d0 + (i * 0x38) as u64)) {
                     Ok(v) => v,
@@ -58,29 +127,19 @@ impl State {
         return false;
     }
 
-    pub fn update(process: &Process, base_addr: Address) -> Self {
+    pub fn update(process: &impl MemorySource, base_addr: Address, watchers: &mut Watchers) -> Self {
         let mut gold_count = 0;
         let mut medal_count = 0;
         let mut goal_count = 0;
 
+        // everything is relative to the loaded modules, so only follow that pointer once
+        let modules = Address::new(*watchers.modules.update(process, base_addr).current() as u64);
+
         // get currently loaded module
-        let module = match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x3c as u64)) {
-            Ok(v) => {
-                match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (v + 0x1c) as u64)) {
-                    Ok(v) => {
-                        //asr::print_message(&format!("MODULE SIZE: {}", v));
-                        if v == 0x43000 {
-                            Module::Frontend
-                        } else if v== 0x9c000 {
-                            Module::Game
-                        } else {
-                            Module::Unknown
-                        }
-                    },
-                    Err(_) => Module::Unknown, 
-                }
-            },
-            Err(_) => Module::Unknown, 
+        let module = if modules.is_null() {
+            Module::Unknown
+        } else {
+            read_module(process, modules)
         };
 
         match module {
@@ -91,47 +150,20 @@ impl State {
                     level_id: 0,
                     mode: 0,
                     module,
-                    _gold_count: 0,
+                    gold_count: 0,
                     medal_count: 0,
                     goal_count: 0,
                     is_loading: false,
                 }
             },
             Module::Frontend => {
-                let rider_id = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x190534 as u64)) {
+                let rider_id = match process.read::<u8>(modules + 0x190534 as u32) {
                     Ok(v) => v,
                     Err(_) => 0xff,
                 };
 
                 if rider_id <= 9 {    // TODO: figure out secret skaters
-                    let rider_profile = 0x850d0 + (rider_id as u32 * 0x38);
-        
-                    match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (rider_profile + 4 + 6) as u64)) {
-                        Ok(v) => {
-                            if v != 0 {
-                                medal_count += 1
-                            }
-                        },
-                        Err(_) => {},
-                    };
-                    match process.read_pointer_path::<u8>(base_addr,asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (rider_profile + 4 + 7) as u64)) {
-                        Ok(v) => {
-                            if v != 0 {
-                                medal_count += 1
-                            }
-                        },
-                        Err(_) => {},
-                    };
-        
-                    goal_count = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, rider_profile as u64)) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    };
-
-                    gold_count = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (rider_profile + 3) as u64)) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    };
+                    (goal_count, gold_count, medal_count) = read_rider_profile(process, modules + 0x850d0 as u32 + rider_id as u32 * 0x38);
                 }
 
                 Self {
@@ -140,100 +172,49 @@ impl State {
                     level_id: 0,
                     mode: 0,
                     module,
-                    _gold_count: gold_count as u32,
+                    gold_count: gold_count as u32,
                     medal_count,
                     goal_count,
-                    is_loading: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x84824 as u64)) {
+                    is_loading: match process.read::<u32>(modules + 0x84824 as u32) {
                         Ok(v) => v == 0,
                         Err(_) => false,
                     },
                 }
             },
             Module::Game => {
-                let rider_id = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x220a96 as u64)) {
+                let rider_id = match process.read::<u8>(modules + 0x220a96 as u32) {
                     Ok(v) => v,
                     Err(_) => 0xff,
                 };
 
                 if rider_id <= 9 {
-                    let rider_profile = 0x221290 + (rider_id as u32 * 0x38);
-        
-                    match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (rider_profile + 4 + 6) as u64)) {
-                        Ok(v) => {
-                            if v != 0 {
-                                medal_count += 1
-                            }
-                        },
-                        Err(_) => {},
-                    };
-                    match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (rider_profile + 4 + 7) as u64)) {
-                        Ok(v) => {
-                            if v != 0 {
-                                medal_count += 1
-                            }
-                        },
-                        Err(_) => {},
-                    };
-        
-                    goal_count = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, rider_profile as u64)) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    };
-
-                    gold_count = match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, (rider_profile + 3) as u64)) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    };
+                    (goal_count, gold_count, medal_count) = read_rider_profile(process, modules + 0x221290 as u32 + rider_id as u32 * 0x38);
                 }
-        
+
+                // possibly CBruce + 2cc0 is time left??
+                let timer_level = (*watchers.timer_level.update(process, base_addr).current()).clamp(0, 13);
+                let is_comp = timer_level == 6 || timer_level == 7;
+
+                let max_time = if is_comp {
+                    1 * 60 * 60 // 1 minutes * 60 seconds * 60 vblanks/sec
+                } else {
+                    2 * 60 * 60 // 2 minutes * 60 seconds * 60 vblanks/sec
+                };
+
                 State {
-                    is_timer_running: match process.read_pointer_path::<bool>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0xc6438 as u64)) {
-                        Ok(v) => v,
-                        Err(_) => false,
-                    },
-        
-                    level_id: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x220a93 as u64)) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    },
-        
-                    mode: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0x220ab0 as u64)) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    },
-        
+                    is_timer_running: *watchers.is_timer_running.update(process, modules).current(),
+                    level_id: *watchers.level_id.update(process, modules).current(),
+                    mode: *watchers.mode.update(process, modules).current(),
                     module,
-        
-                    // used for igt only, so clamp it to max run time
-                    _timer_vblanks: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0xc61dc as u64)) {
-                        Ok(v) => {
-                            // possibly CBruce + 2cc0 is time left??
-                            let level_id = match process.read::<u32>(base_addr + 0x15e8f0 as u32) {
-                                Ok(v) => v.clamp(0, 13),
-                                Err(_) => 0,
-                            };
-        
-                            let is_comp = level_id == 6 || level_id == 7;
-            
-                            let max_time = if is_comp {
-                                1 * 60 * 60 // 1 minutes * 60 seconds * 60 vblanks/sec
-                            } else {
-                                2 * 60 * 60 // 2 minutes * 60 seconds * 60 vblanks/sec
-                            };
-        
-                            (v + 30).clamp(0, max_time)   // timer sticks at 2:00 for half a second, add 30 vblanks to account for this
-                        },
-                        Err(_) => 0,
-                    },
-        
-                    _gold_count: gold_count as u32,
+
+                    // used for igt only, so clamp it to max run time.  the timer sticks at 2:00 for half a second, add 30 vblanks to account for this
+                    _timer_vblanks: (watchers.timer_vblanks.update(process, modules).current() + 30).clamp(0, max_time),
+
+                    gold_count: gold_count as u32,
                     medal_count: medal_count,
                     goal_count: goal_count,
 
-                    is_loading: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0xbc6b0 as u64)) {
-                        Ok(v) => v != 0,
-                        Err(_) => false,
-                    } || match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x139db4 as u64, 0xbc598 as u64)) {
+                    is_loading: *watchers.is_loading.update(process, modules).current() != 0 || match process.read::<u8>(modules + 0xbc598 as u32) {
                         Ok(v) => v == 0,
                         Err(_) => false,
                     },
@@ -241,56 +222,105 @@ impl State {
             }
         }
     }
-}
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to MHPB!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Goals", self.goal_count);
+        variables.set("Medals", self.medal_count);
+        variables.set("Gold Medals", self.gold_count);
+    }
+}
 
-    let base_addr = process.get_module_address(process_name).unwrap();
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("timer_running", self.is_timer_running);
+        w.field("vblanks", self._timer_vblanks);
+        w.field("level", self.level_id);
+        w.field("mode", self.mode);
+        w.field("module", self.module as u8);
+        w.field("golds", self.gold_count);
+        w.field("medals", self.medal_count);
+        w.field("goals", self.goal_count);
+        w.field("loading", self.is_loading);
+    }
 
-    let mut prev_state = State::update(process, base_addr);
-    let mut game_done = false;
-    let mut level_changed = false;
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            is_timer_running: r.field("timer_running")?,
+            _timer_vblanks: r.field("vblanks")?,
+            level_id: r.field("level")?,
+            mode: r.field("mode")?,
+            module: Module::from_id(r.field("module")?),
+            gold_count: r.field("golds")?,
+            medal_count: r.field("medals")?,
+            goal_count: r.field("goals")?,
+            is_loading: r.field("loading")?,
+        })
+    }
+}
 
-    let mut _igt_accumulator: i64 = 0;   // igt in seconds
-    let mut _prev_igt = Duration::seconds(-1);
-    let mut _start_vblank = 0;
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+    game_done: bool,
+    level_changed: bool,
+    _igt_accumulator: i64,   // igt in seconds
+    _prev_igt: Duration,
+    _start_vblank: u32,
+    prev_level: u8,
+}
 
-    let mut prev_level = 0;
+impl GameSplitter for Splitter {
+    type State = State;
+
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+            game_done: false,
+            level_changed: false,
+            _igt_accumulator: 0,
+            _prev_igt: Duration::seconds(-1),
+            _start_vblank: 0,
+            prev_level: 0,
+        }
+    }
 
-    loop {
-        // update vars
-        let mut current_state = State::update(process, base_addr);
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, mut current_state: State) {
+        let prev_state = &self.prev_state;
 
         // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
+        if is_loading.changed_to(&true) {
+            timer.pause_game_time();
+            debug!("Starting Load...");
+        } else if is_loading.changed_to(&false) {
+            timer.resume_game_time();
+            debug!("Done Loading");
         }
 
-        match asr::timer::state() {
+        match timer.state() {
             TimerState::NotRunning => {
-                if game_done {
-                    game_done = false;
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+
+                if self.game_done {
+                    self.game_done = false;
                 }
 
-                if matches!(current_state.module, Module::Game) && current_state.mode == 0 && current_state.goal_count == 0 && current_state.level_id == 0 && current_state.is_timer_running {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer...").as_str());
+                if settings.mhpb_start && matches!(current_state.module, Module::Game) && current_state.mode == 0 && current_state.goal_count == 0 && current_state.level_id == 0 && current_state.is_timer_running {
+                    timer.start();
+                    info!("Starting timer...");
 
-                    //asr::timer::pause_game_time();
-                    _igt_accumulator = 0;
-                    prev_level = 0;
+                    //timer.pause_game_time();
+                    self._igt_accumulator = 0;
+                    self.prev_level = 0;
                 }
             },
             TimerState::Paused | TimerState::Running => {
                 // level id will often change to 0 on menu, so preserve it from last gameplay
                 if matches!(current_state.module, Module::Frontend) {
-                    current_state.level_id = prev_level;
+                    current_state.level_id = self.prev_level;
                 }
 
                 // keep level id stable if the module is still initializing
@@ -301,33 +331,44 @@ pub async fn run(process: &Process, process_name: &str) {
                 // make sure level is valid
                 if matches!(current_state.module, Module::Game) && !current_state.is_loading {
                     // split on level changes (except going to menu)
-                    if matches!(current_state.module, Module::Game) && current_state.level_id != prev_level {
-                        level_changed = true;
+                    if matches!(current_state.module, Module::Game) && current_state.level_id != self.prev_level {
+                        self.level_changed = true;
                     }
-                    prev_level = current_state.level_id;
+                    self.prev_level = current_state.level_id;
                 }
 
-                if matches!(current_state.module, Module::Game) && level_changed {
-                    level_changed = false;
-                    asr::timer::split();
-                    asr::print_message(format!("Changed levels; splitting timer...").as_str());
+                if matches!(current_state.module, Module::Game) && self.level_changed {
+                    self.level_changed = false;
+
+                    if settings.mhpb_split_level {
+                        timer.split();
+                        info!("Changed levels; splitting timer...");
+                    }
                 }
 
-                // split when all medals collected 
-                // TODO: add setting to only split when all goals and goals are collected
-                if matches!(current_state.module, Module::Game) && !game_done && current_state.medal_count == 2 {
-                    game_done = true;
-                    asr::timer::split();
-                    asr::print_message(format!("Collected all medals; splitting timer...").as_str());
+                // split when all medals (or golds) are collected
+                let is_category_done = match settings.mhpb_category {
+                    MhpbCategory::AllMedals => current_state.medal_count == 2,
+                    MhpbCategory::AllGolds => current_state.gold_count == 2,
+                };
+
+                if settings.mhpb_split_medals && matches!(current_state.module, Module::Game) && !self.game_done && is_category_done {
+                    self.game_done = true;
+                    timer.split();
+
+                    match settings.mhpb_category {
+                        MhpbCategory::AllMedals => info!("Collected all medals; splitting timer..."),
+                        MhpbCategory::AllGolds => info!("Collected all golds; splitting timer..."),
+                    }
                 }
 
                 // reset when on a menu and no goals are complete on current rider
-                if matches!(current_state.module, Module::Frontend) && !current_state.is_loading && current_state.goal_count == 0 {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
+                if settings.mhpb_reset && !self.continuing_series && matches!(current_state.module, Module::Frontend) && !current_state.is_loading && current_state.goal_count == 0 {
+                    timer.reset();
+                    info!("Resetting timer...");
 
-                    _prev_igt = Duration::seconds(-1);
-                    //asr::timer::resume_game_time();
+                    self._prev_igt = Duration::seconds(-1);
+                    //timer.resume_game_time();
                 }
 
                 // calculate igt
@@ -351,7 +392,7 @@ pub async fn run(process: &Process, process_name: &str) {
                     // prevent excess messaging and only send igt when relevant
                     if igt_duration != prev_igt {
                         prev_igt = igt_duration;
-                        asr::timer::set_game_time(igt_duration);
+                        timer.set_game_time(igt_duration);
                     }
                 }*/
             },
@@ -360,8 +401,127 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to MHPB!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let base_addr = attach::module_address(process, process_name).await;
+
+    let offsets = OFFSETS;
+
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("MHPB");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
+
+    loop {
+        settings.refresh();
+
+        // update vars
+        let current_state = State::update(process, base_addr, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
+
+    const BASE: u32 = 0x400000;
+    const MODULES: u32 = 0x20000000;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+    }
+
+    impl Game {
+        // starts in the game module, with the first rider's profile empty
+        fn new() -> Self {
+            let offsets = OFFSETS;
+            let mut memory = MockMemory::new();
+            memory.write(BASE + offsets.modules, MODULES);
+            memory.write(MODULES + 0x3c, 0x80u32);
+            memory.write(MODULES + 0x80 + 0x1c, 0x9c000u32);
+            memory.write(MODULES + 0x220a96, 0u8);
+            memory.write_bytes(MODULES + 0x221290, &[0; 0x38]);
+            memory.write(MODULES + 0xbc598, 1u8);
+
+            let mut watchers = Watchers::new(&offsets);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE as u64), &mut watchers), false);
+
+            Self { memory, watchers, splitter, timer: ReplayTimer::new(None) }
+        }
+
+        fn set(&mut self, offset: u32, value: u8) {
+            self.memory.write(MODULES + offset, value);
+        }
+
+        // swaps in the frontend, with the first rider's profile empty
+        fn go_to_frontend(&mut self) {
+            self.memory.write(MODULES + 0x80 + 0x1c, 0x43000u32);
+            self.memory.write(MODULES + 0x190534, 0u8);
+            self.memory.write_bytes(MODULES + 0x850d0, &[0; 0x38]);
+            self.memory.write(MODULES + 0x84824, 1u32);
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = State::update(&self.memory, Address::new(BASE as u64), &mut self.watchers);
+            self.splitter.update(&mut self.timer, &Settings::default(), state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_on_the_first_run_and_splits_on_both_medals() {
+        let mut game = Game::new();
+        assert!(game.tick().is_empty());
+
+        game.set(0xc6438, 1);
+        assert_eq!(game.tick(), [Start]);
+
+        game.set(0x221290 + 4 + 6, 1);
+        assert!(game.tick().is_empty());
+
+        game.set(0x221290 + 4 + 7, 1);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn pauses_game_time_while_loading() {
+        let mut game = Game::new();
+        game.set(0xbc6b0, 1);
+        assert_eq!(game.tick(), [PauseGameTime]);
+
+        game.set(0xbc6b0, 0);
+        assert_eq!(game.tick(), [ResumeGameTime]);
+    }
+
+    #[test]
+    fn resets_on_the_frontend_without_goals() {
+        let mut game = Game::new();
+        game.timer.start();
+        assert!(game.tick().is_empty());
+
+        game.go_to_frontend();
+        assert_eq!(game.tick(), [Reset]);
+    }
+}
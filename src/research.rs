@@ -0,0 +1,154 @@
+// research mode, for finding new values without an external memory scanner.  a region of game memory (set by hand in the settings) is
+// copied every tick, and whenever one of the published variables changes, every word in the region that changed over the last few ticks is
+// logged.  e.g. to look for a secret skater flag, point the region at the career struct and watch what changes alongside "Goals"
+
+use std::collections::VecDeque;
+
+use asr::{Address, PointerSize};
+
+use crate::{memory::MemorySource, settings::{MemoryRegion, Settings}, variables::Variables};
+
+// copying the region happens every tick, so keep it from getting out of hand
+const MAX_REGION_SIZE: u32 = 0x10000;
+
+// past this many changed words, only the count is logged
+const MAX_LOGGED_CHANGES: usize = 64;
+
+// the games often update a value a tick or two before or after the one that's published, so changes are taken against the region as it was
+// this many ticks ago instead of just the last one
+const HISTORY_TICKS: usize = 16;
+
+pub struct Researcher {
+    pointer_size: PointerSize,
+    region: MemoryRegion,
+    history: VecDeque<(Address, Vec<u8>)>,  // oldest first
+}
+
+impl Researcher {
+    pub fn new(pointer_size: PointerSize) -> Self {
+        Self {
+            pointer_size,
+            region: MemoryRegion::default(),
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn update(&mut self, process: &impl MemorySource, base_addr: Address, settings: &Settings, variables: &mut Variables) {
+        let changes = variables.take_changes();
+
+        if !settings.research_mode || settings.research_region.path.is_empty() {
+            self.history.clear();
+            return;
+        }
+
+        if settings.research_region != self.region {
+            self.region = settings.research_region.clone();
+            self.history.clear();
+
+            if self.region.len > MAX_REGION_SIZE {
+                warn!("Research region is too big; only the first {:#x} bytes are watched", MAX_REGION_SIZE);
+            }
+            info!("Researching {} bytes at {}", self.region.len.min(MAX_REGION_SIZE), self.region);
+        }
+
+        let Some((addr, bytes)) = self.snapshot(process, base_addr) else {
+            self.history.clear();
+            return;
+        };
+
+        // older copies of a region that moved don't compare with the new one
+        if let Some(&(old_addr, _)) = self.history.back() {
+            if old_addr != addr {
+                if !changes.is_empty() {
+                    info!("Research region moved from {:#x} to {:#x} ({})", old_addr.value(), addr.value(), changes.join(", "));
+                }
+                self.history.clear();
+            }
+        }
+
+        if let Some((_, oldest)) = self.history.front() {
+            if !changes.is_empty() {
+                for line in describe_changes(addr, oldest, &bytes, &changes) {
+                    info!("{}", line);
+                }
+            }
+        }
+
+        if self.history.len() == HISTORY_TICKS {
+            self.history.pop_front();
+        }
+        self.history.push_back((addr, bytes));
+    }
+
+    // the region's address and contents, or None if the path or the region can't be read right now (e.g. a struct that isn't allocated yet)
+    fn snapshot(&self, process: &impl MemorySource, base_addr: Address) -> Option<(Address, Vec<u8>)> {
+        let (&last, path) = self.region.path.split_last()?;
+
+        let mut address = base_addr;
+        for &offset in path {
+            address = process.read_pointer(address + offset, self.pointer_size).ok()?;
+        }
+        address = address + last;
+
+        let mut bytes = vec![0u8; self.region.len.min(MAX_REGION_SIZE) as usize];
+        process.read_bytes(address, &mut bytes).ok()?;
+
+        Some((address, bytes))
+    }
+}
+
+// compares the region 4 bytes at a time, since nearly everything the games keep track of is a 32 bit int, float or set of flags.  gives the lines to log
+fn describe_changes(addr: Address, old: &[u8], current: &[u8], changes: &[String]) -> Vec<String> {
+    let changed: Vec<(usize, u32, u32)> = old.chunks_exact(4).zip(current.chunks_exact(4)).enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(i, (a, b))| (i * 4, u32::from_le_bytes([a[0], a[1], a[2], a[3]]), u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+        .collect();
+
+    let mut lines = vec![format!("Research: {} changed {} words in the region at {:#x}", changes.join(", "), changed.len(), addr.value())];
+
+    for &(offset, a, b) in changed.iter().take(MAX_LOGGED_CHANGES) {
+        lines.push(format!("  +{:#x} ({:#x}): {:#010x} -> {:#010x} ({:+})", offset, addr.value() + offset as u64, a, b, (b as i32).wrapping_sub(a as i32)));
+    }
+
+    if changed.len() > MAX_LOGGED_CHANGES {
+        lines.push(format!("  ...and {} more", changed.len() - MAX_LOGGED_CHANGES));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn describes_each_changed_word() {
+        let lines = describe_changes(Address::new(0x1000), &words(&[1, 2, 3]), &words(&[1, 5, 2]), &["Goals: 0 -> 1".to_string()]);
+
+        assert_eq!(lines, [
+            "Research: Goals: 0 -> 1 changed 2 words in the region at 0x1000",
+            "  +0x4 (0x1004): 0x00000002 -> 0x00000005 (+3)",
+            "  +0x8 (0x1008): 0x00000003 -> 0x00000002 (-1)",
+        ]);
+    }
+
+    #[test]
+    fn nothing_changed_is_still_reported() {
+        let lines = describe_changes(Address::new(0x1000), &words(&[1]), &words(&[1]), &["Level: 1 -> 2".to_string()]);
+        assert_eq!(lines, ["Research: Level: 1 -> 2 changed 0 words in the region at 0x1000"]);
+    }
+
+    #[test]
+    fn only_counts_changes_past_the_limit() {
+        let old = words(&[0; MAX_LOGGED_CHANGES + 3]);
+        let current = words(&[1; MAX_LOGGED_CHANGES + 3]);
+        let lines = describe_changes(Address::new(0x1000), &old, &current, &["Goals: 0 -> 1".to_string()]);
+
+        assert_eq!(lines.len(), 1 + MAX_LOGGED_CHANGES + 1);
+        assert_eq!(lines.last().unwrap(), "  ...and 3 more");
+    }
+}
@@ -0,0 +1,57 @@
+// finds a game's root pointers by scanning its executable for code that uses them, so rebuilt exes (partymod, clownjob'd, ...)
+// still work when everything has moved.  games list the signatures they know about, and the first one that matches is used.  games that
+// also have a known offset for the stock exe fall back to it when nothing matches
+
+use asr::{Address, Process};
+
+pub struct RootSignature {
+    pub scan: fn(&Process, (Address, u64)) -> Option<Address>,     // finds where the pointer's absolute address is stored in the code
+}
+
+// builds a RootSignature from the pattern's length in bytes, the pattern, and how far into the match the address is
+macro_rules! root_signature {
+    ($len:literal, $pattern:literal, $offset:literal) => {
+        $crate::scan::RootSignature {
+            scan: |process, range| Some(asr::signature::Signature::<$len>::new($pattern).scan_process_range(process, range)? + $offset as u32),
+        }
+    };
+}
+
+// the pointer as an offset from the module, from the first signature that matches
+pub fn resolve(process: &Process, base_addr: Address, module_size: u64, what: &str, signatures: &[RootSignature]) -> Option<u32> {
+    for signature in signatures {
+        let Some(addr) = (signature.scan)(process, (base_addr, module_size)) else {
+            continue;
+        };
+
+        // the code holds an absolute address, so make it relative to the module
+        let offset = match process.read::<u32>(addr) {
+            Ok(v) => (v as u64).checked_sub(base_addr.value()).filter(|v| *v < module_size),
+            Err(_) => None,
+        };
+
+        if let Some(v) = offset {
+            info!("Found {} by signature scan ({:#x})", what, v);
+            return Some(v as u32);
+        }
+    }
+
+    warn!("Couldn't find {} by signature scan", what);
+    None
+}
+
+// like resolve, but falls back to the stock exe's offset when no signature matches.  the log says which one was used
+pub fn resolve_or(process: &Process, base_addr: Address, module_size: u64, what: &str, signatures: &[RootSignature], fallback: u32) -> u32 {
+    if signatures.is_empty() {
+        info!("No signatures for {}; using the static offset ({:#x})", what, fallback);
+        return fallback;
+    }
+
+    match resolve(process, base_addr, module_size, what, signatures) {
+        Some(v) => v,
+        None => {
+            info!("Using the static offset for {} ({:#x})", what, fallback);
+            fallback
+        },
+    }
+}
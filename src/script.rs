@@ -0,0 +1,274 @@
+// reader for the script structures neversoft games keep in memory (THUG2 and THAW so far).  a struct is a linked list of named components,
+// and each component holds a value whose type is stored alongside it.  reading one gives back a tree of typed values, so a game module can
+// ask for a path of names instead of walking the lists by hand
+
+use asr::Address;
+
+use crate::memory::MemorySource;
+
+// component types, as stored in the second byte of a component (shifted left by one)
+const TYPE_INTEGER: u8 = 0x1;
+const TYPE_FLOAT: u8 = 0x2;
+const TYPE_STRING: u8 = 0x3;
+const TYPE_LOCAL_STRING: u8 = 0x4;
+const TYPE_STRUCT: u8 = 0xa;
+const TYPE_ARRAY: u8 = 0xc;
+const TYPE_CHECKSUM: u8 = 0xd;
+
+// limits so a struct that's being freed (or a bad pointer) can't send the reader off into garbage forever
+const MAX_DEPTH: u32 = 8;
+const MAX_COMPONENTS: usize = 4096;
+const MAX_ARRAY_SIZE: u32 = 4096;
+const MAX_STRING_LENGTH: usize = 128;
+
+const STRING_CHUNK_SIZE: u32 = 32;
+const PAGE_SIZE: u32 = 0x1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptValue {
+    Integer(i32),
+    Float(f32),
+    Checksum(u32),
+    String(String),
+    Struct(ScriptStruct),
+    Array(Vec<ScriptValue>),
+    #[allow(dead_code)]
+    Other { ttype: u8, data: u32 },     // pairs, vectors, scripts, etc.  nothing needs these yet
+}
+
+// not every game needs every type
+#[allow(dead_code)]
+impl ScriptValue {
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            ScriptValue::Integer(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f32> {
+        match self {
+            ScriptValue::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_checksum(&self) -> Option<u32> {
+        match self {
+            ScriptValue::Checksum(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ScriptValue::String(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_struct(&self) -> Option<&ScriptStruct> {
+        match self {
+            ScriptValue::Struct(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[ScriptValue]> {
+        match self {
+            ScriptValue::Array(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+
+    // None when a nested struct or array fails to read, so a half read value never passes for a whole one
+    fn read(process: &impl MemorySource, ttype: u8, data: u32, depth: u32) -> Option<Self> {
+        let value = match ttype {
+            TYPE_INTEGER => ScriptValue::Integer(data as i32),
+            TYPE_FLOAT => ScriptValue::Float(f32::from_bits(data)),
+            TYPE_CHECKSUM => ScriptValue::Checksum(data),
+            TYPE_STRING | TYPE_LOCAL_STRING => ScriptValue::String(read_string(process, data)),
+            TYPE_STRUCT if depth < MAX_DEPTH && data != 0 => ScriptValue::Struct(ScriptStruct::read_at_depth(process, data, depth + 1)?),
+            TYPE_ARRAY if depth < MAX_DEPTH && data != 0 => ScriptValue::Array(read_array(process, data, depth + 1)?),
+            _ => ScriptValue::Other { ttype, data },
+        };
+
+        Some(value)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScriptStruct {
+    pub components: Vec<(u32, ScriptValue)>,    // (name checksum, value) in list order.  unnamed components have a name of 0
+}
+
+impl ScriptStruct {
+    // None if any part of the struct can't be read, e.g. while it's being freed or rebuilt
+    pub fn read(process: &impl MemorySource, addr: u32) -> Option<Self> {
+        Self::read_at_depth(process, addr, 0)
+    }
+
+    fn read_at_depth(process: &impl MemorySource, addr: u32, depth: u32) -> Option<Self> {
+        if addr == 0 {
+            return None;
+        }
+
+        let mut comp = match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
+            Ok(v) => v,
+            Err(_) => return None,
+        };
+
+        let mut components = Vec::new();
+        while comp != 0 && components.len() < MAX_COMPONENTS {
+            // [unknown, type, size], name, data, next
+            let [header, name, data, pnext] = match process.read::<[u32; 4]>(comp) {
+                Ok(v) => v,
+                Err(_) => return None,
+            };
+
+            let ttype = (header >> 8) as u8 >> 1;
+            components.push((name, ScriptValue::read(process, ttype, data, depth)?));
+
+            comp = pnext;
+        }
+
+        Some(Self { components })
+    }
+
+    // the first component with this name
+    pub fn field(&self, name: u32) -> Option<&ScriptValue> {
+        self.components.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+
+    // follows a path of names through nested structs, e.g. goalmanager_params -> difficulty_levels -> career
+    pub fn get(&self, path: &[u32]) -> Option<&ScriptValue> {
+        let (&last, path) = path.split_last()?;
+
+        let mut current = self;
+        for &name in path {
+            current = current.field(name)?.as_struct()?;
+        }
+
+        current.field(last)
+    }
+}
+
+// arrays are laid out as [unknown, type, ...], size, data, and the data holds one 4 byte value (or pointer) per element
+// only the size and data pointer have been confirmed (in THAW); the element type is assumed to sit where a component keeps its type
+fn read_array(process: &impl MemorySource, addr: u32, depth: u32) -> Option<Vec<ScriptValue>> {
+    if addr == 0 {
+        return None;
+    }
+
+    let [header, size, pdata] = match process.read::<[u32; 3]>(addr) {
+        Ok(v) => v,
+        Err(_) => return None,
+    };
+
+    if size > MAX_ARRAY_SIZE {
+        return None;
+    }
+
+    let ttype = (header >> 8) as u8 >> 1;
+    let mut result = Vec::with_capacity(size as usize);
+    for i in 0..size {
+        let data = match process.read::<u32>(pdata.wrapping_add(i * 4)) {
+            Ok(v) => v,
+            Err(_) => return None,
+        };
+
+        result.push(ScriptValue::read(process, ttype, data, depth)?);
+    }
+
+    Some(result)
+}
+
+// strings are read a chunk at a time.  chunks stop at page boundaries, so a string that ends just before unreadable memory still reads
+fn read_string(process: &impl MemorySource, addr: u32) -> String {
+    let mut bytes = Vec::new();
+
+    while bytes.len() < MAX_STRING_LENGTH {
+        let chunk_addr = addr.wrapping_add(bytes.len() as u32);
+        let len = STRING_CHUNK_SIZE.min(PAGE_SIZE - chunk_addr % PAGE_SIZE) as usize;
+
+        let mut chunk = [0u8; STRING_CHUNK_SIZE as usize];
+        if process.read_bytes(Address::new(chunk_addr as u64), &mut chunk[..len]).is_err() {
+            break;
+        }
+
+        match chunk[..len].iter().position(|b| *b == 0) {
+            Some(end) => {
+                bytes.extend_from_slice(&chunk[..end]);
+                break;
+            },
+            None => bytes.extend_from_slice(&chunk[..len]),
+        }
+    }
+
+    bytes.truncate(MAX_STRING_LENGTH);
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MockMemory;
+
+    // the rest of the page after the string is zeroed, like a real heap would be readable
+    fn string_at(addr: u32, text: &str) -> String {
+        let end = addr + text.len() as u32;
+        let mut memory = MockMemory::new();
+        memory.write_bytes(addr, text.as_bytes());
+        memory.write_bytes(end, &vec![0; (PAGE_SIZE - end % PAGE_SIZE) as usize]);
+
+        read_string(&memory, addr)
+    }
+
+    #[test]
+    fn reads_strings_longer_than_a_chunk() {
+        let text = "a string that takes more than one chunk to read";
+        assert_eq!(string_at(0x10000, text), text);
+    }
+
+    #[test]
+    fn reads_strings_that_end_next_to_unreadable_memory() {
+        // the terminator is the last readable byte of the page
+        assert_eq!(string_at(0x10ffa, "Intro"), "Intro");
+    }
+
+    #[test]
+    fn stops_at_the_maximum_length() {
+        let text = "x".repeat(MAX_STRING_LENGTH + 10);
+        assert_eq!(string_at(0x10000, &text).len(), MAX_STRING_LENGTH);
+    }
+
+    #[test]
+    fn unreadable_strings_are_empty() {
+        assert_eq!(read_string(&MockMemory::new(), 0x10000), "");
+    }
+
+    // a struct at 0x20000 with one integer and one nested struct, which holds one integer
+    fn nested_struct() -> MockMemory {
+        let mut memory = MockMemory::new();
+        memory.write(0x20004u32, 0x20100u32);
+        memory.write(0x20100u32, [(TYPE_INTEGER as u32) << 9, 0x1111, 5, 0x20110]);
+        memory.write(0x20110u32, [(TYPE_STRUCT as u32) << 9, 0x2222, 0x20200, 0]);
+        memory.write(0x20204u32, 0x20300u32);
+        memory.write(0x20300u32, [(TYPE_INTEGER as u32) << 9, 0x3333, 7, 0]);
+        memory
+    }
+
+    #[test]
+    fn reads_nested_structs() {
+        let root = ScriptStruct::read(&nested_struct(), 0x20000).unwrap();
+        assert_eq!(root.field(0x1111), Some(&ScriptValue::Integer(5)));
+        assert_eq!(root.get(&[0x2222, 0x3333]), Some(&ScriptValue::Integer(7)));
+    }
+
+    #[test]
+    fn unreadable_nested_structs_fail_the_whole_read() {
+        let mut memory = nested_struct();
+        memory.write(0x20300u32, [(TYPE_INTEGER as u32) << 9, 0x3333, 7, 0x20400]);
+        assert_eq!(ScriptStruct::read(&memory, 0x20000), None);
+    }
+}
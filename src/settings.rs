@@ -0,0 +1,547 @@
+// user-facing settings shown in livesplit's autosplitter settings
+
+use std::fmt;
+
+use asr::settings::{Gui, Map, gui::{Title, Widget}};
+
+use crate::{checksum, log};
+
+#[derive(Gui)]
+pub struct Settings {
+    /// Series Runs
+    #[heading_level = 0]
+    _series: Title,
+
+    /// Keep the timer running between games
+    ///
+    /// When a game closes, the timer keeps running and the next game picks up the run instead of resetting.
+    #[default = false]
+    pub series_mode: bool,
+
+    /// Split when switching games
+    ///
+    /// Only used in series runs: splits when the next game in the series is detected.
+    #[default = false]
+    pub split_on_game_change: bool,
+
+    /// Tony Hawk's Pro Skater 2
+    #[heading_level = 0]
+    _thps2: Title,
+
+    /// Start when a new career is started
+    #[default = true]
+    pub thps2_start: bool,
+
+    /// Reset when no skater has any goals completed
+    #[default = true]
+    pub thps2_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub thps2_split_level: bool,
+
+    /// Final split
+    pub thps2_category: Thps2Category,
+
+    /// Tony Hawk's Pro Skater 3
+    #[heading_level = 0]
+    _thps3: Title,
+
+    /// Start when entering Foundry on a new career
+    #[default = true]
+    pub thps3_start: bool,
+
+    /// Reset when returning to the skateshop with no goals completed
+    #[default = true]
+    pub thps3_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub thps3_split_level: bool,
+
+    /// Category
+    pub thps3_category: Thps3Category,
+
+    /// Tony Hawk's Pro Skater 4
+    #[heading_level = 0]
+    _thps4: Title,
+
+    /// Start when entering College on a new career
+    #[default = true]
+    pub thps4_start: bool,
+
+    /// Reset when on the skateshop with no pro points
+    #[default = true]
+    pub thps4_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub thps4_split_level: bool,
+
+    /// Split on first pro goal completed
+    #[default = true]
+    pub thps4_split_pro_goal: bool,
+
+    /// Split when all goals are completed (190 pro points)
+    #[default = true]
+    pub thps4_split_all_goals: bool,
+
+    /// Split when all cash is collected
+    #[default = true]
+    pub thps4_split_all_cash: bool,
+
+    /// Tony Hawk's Underground
+    #[heading_level = 0]
+    _thug1: Title,
+
+    /// Start after the intro cutscene
+    #[default = true]
+    pub thug1_start: bool,
+
+    /// Reset on the main menu when no career is started
+    #[default = true]
+    pub thug1_reset: bool,
+
+    /// Split when starting each story chapter
+    #[default = true]
+    pub thug1_split_chapter: bool,
+
+    /// Split when the story is finished
+    #[default = true]
+    pub thug1_split_final: bool,
+
+    /// Tony Hawk's Underground 2
+    #[heading_level = 0]
+    _thug2: Title,
+
+    /// Start when starting story mode
+    #[default = true]
+    pub thug2_start_story: bool,
+
+    /// Start when starting classic mode
+    #[default = true]
+    pub thug2_start_classic: bool,
+
+    /// Reset on the main menu with no progress
+    #[default = true]
+    pub thug2_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub thug2_split_level: bool,
+
+    /// Split on the final story cutscene
+    #[default = true]
+    pub thug2_split_final: bool,
+
+    /// Split at the end of classic mode
+    #[default = true]
+    pub thug2_split_classic_end: bool,
+
+    /// Split on every goal completed
+    #[default = false]
+    pub thug2_split_goal: bool,
+
+    /// Split when every story goal is completed (100%)
+    #[default = false]
+    pub thug2_split_story_complete: bool,
+
+    /// Split when every unlocked story goal on a level is completed
+    #[default = false]
+    pub thug2_split_level_complete: bool,
+
+    /// Tony Hawk's American Wasteland
+    #[heading_level = 0]
+    _thaw: Title,
+
+    /// Start when starting story mode
+    #[default = true]
+    pub thaw_start_story: bool,
+
+    /// Start when starting classic mode
+    #[default = true]
+    pub thaw_start_classic: bool,
+
+    /// Reset on the main menu with no goals completed
+    #[default = true]
+    pub thaw_reset: bool,
+
+    /// Split on story milestones (level unlocks, AmJam, Oil Rig...)
+    #[default = true]
+    pub thaw_split_milestones: bool,
+
+    /// Split when the final story goal is completed
+    #[default = true]
+    pub thaw_split_final: bool,
+
+    /// Split on level change in classic mode
+    #[default = true]
+    pub thaw_split_classic_level: bool,
+
+    /// Split at the end of classic mode
+    #[default = true]
+    pub thaw_split_classic_end: bool,
+
+    /// Custom story splits (set by hand, see the README)
+    pub thaw_story_goals: GoalList,
+
+    /// Tony Hawk's Pro Skater 1 + 2
+    #[heading_level = 0]
+    _thps12: Title,
+
+    /// Start when starting a new career
+    #[default = true]
+    pub thps12_start: bool,
+
+    /// Reset on the frontend with no goals completed
+    #[default = true]
+    pub thps12_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub thps12_split_level: bool,
+
+    /// Split on Roswell and Bullring medals
+    #[default = true]
+    pub thps12_split_medal: bool,
+
+    /// Category
+    pub thps12_category: Thps12Category,
+
+    /// Timing
+    pub thps12_timing: AlcatrazTiming,
+
+    /// Tony Hawk's Pro Skater 3 + 4
+    #[heading_level = 0]
+    _thps34: Title,
+
+    /// Start when starting a new career
+    #[default = true]
+    pub thps34_start: bool,
+
+    /// Reset on the frontend with no goals completed
+    #[default = true]
+    pub thps34_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub thps34_split_level: bool,
+
+    /// Split when ending a run with a new star
+    #[default = true]
+    pub thps34_split_star: bool,
+
+    /// Timing
+    pub thps34_timing: AlcatrazTiming,
+
+    /// Mat Hoffman's Pro BMX
+    #[heading_level = 0]
+    _mhpb: Title,
+
+    /// Start when starting a new career
+    #[default = true]
+    pub mhpb_start: bool,
+
+    /// Reset on a menu with no goals completed
+    #[default = true]
+    pub mhpb_reset: bool,
+
+    /// Split on level change
+    #[default = true]
+    pub mhpb_split_level: bool,
+
+    /// Split when all medals (or golds) are collected
+    #[default = true]
+    pub mhpb_split_medals: bool,
+
+    /// Final split
+    pub mhpb_category: MhpbCategory,
+
+    /// Debugging
+    #[heading_level = 0]
+    _debugging: Title,
+
+    /// Record a state trace
+    ///
+    /// Logs the game state every time it changes, so missed or double splits can be replayed later.
+    #[default = false]
+    pub record_trace: bool,
+
+    /// Log detail
+    ///
+    /// How much the autosplitter writes to the log.  Debug and trace add diagnostics that are mostly useful when reporting a problem.
+    pub log_level: log::Level,
+
+    /// Research mode
+    ///
+    /// Logs which values in the research region changed whenever a layout variable (Level, Goals, ...) changes.  Used to find new addresses, see the README.
+    #[default = false]
+    pub research_mode: bool,
+
+    /// Research region (set by hand, see the README)
+    pub research_region: MemoryRegion,
+}
+
+impl Settings {
+    // pulls the latest values from livesplit, and applies the ones that aren't read directly by the games
+    pub fn refresh(&mut self) {
+        self.update();
+        log::set_max_level(self.log_level);
+    }
+}
+
+// the same defaults the settings gui registers, for when the settings aren't coming from livesplit (e.g. replaying a trace)
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            _series: Title,
+            series_mode: false,
+            split_on_game_change: false,
+
+            _thps2: Title,
+            thps2_start: true,
+            thps2_reset: true,
+            thps2_split_level: true,
+            thps2_category: Thps2Category::default(),
+
+            _thps3: Title,
+            thps3_start: true,
+            thps3_reset: true,
+            thps3_split_level: true,
+            thps3_category: Thps3Category::default(),
+
+            _thps4: Title,
+            thps4_start: true,
+            thps4_reset: true,
+            thps4_split_level: true,
+            thps4_split_pro_goal: true,
+            thps4_split_all_goals: true,
+            thps4_split_all_cash: true,
+
+            _thug1: Title,
+            thug1_start: true,
+            thug1_reset: true,
+            thug1_split_chapter: true,
+            thug1_split_final: true,
+
+            _thug2: Title,
+            thug2_start_story: true,
+            thug2_start_classic: true,
+            thug2_reset: true,
+            thug2_split_level: true,
+            thug2_split_final: true,
+            thug2_split_classic_end: true,
+            thug2_split_goal: false,
+            thug2_split_story_complete: false,
+            thug2_split_level_complete: false,
+
+            _thaw: Title,
+            thaw_start_story: true,
+            thaw_start_classic: true,
+            thaw_reset: true,
+            thaw_split_milestones: true,
+            thaw_split_final: true,
+            thaw_split_classic_level: true,
+            thaw_split_classic_end: true,
+            thaw_story_goals: GoalList::default(),
+
+            _thps12: Title,
+            thps12_start: true,
+            thps12_reset: true,
+            thps12_split_level: true,
+            thps12_split_medal: true,
+            thps12_category: Thps12Category::default(),
+            thps12_timing: AlcatrazTiming::default(),
+
+            _thps34: Title,
+            thps34_start: true,
+            thps34_reset: true,
+            thps34_split_level: true,
+            thps34_split_star: true,
+            thps34_timing: AlcatrazTiming::default(),
+
+            _mhpb: Title,
+            mhpb_start: true,
+            mhpb_reset: true,
+            mhpb_split_level: true,
+            mhpb_split_medals: true,
+            mhpb_category: MhpbCategory::default(),
+
+            _debugging: Title,
+            record_trace: false,
+            log_level: log::Level::default(),
+            research_mode: false,
+            research_region: MemoryRegion::default(),
+        }
+    }
+}
+
+#[derive(Gui, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Thps2Category {
+    /// All medals collected
+    #[default]
+    AllMedals,
+    /// All gold medals collected
+    AllGolds,
+}
+
+#[derive(Gui, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Thps3Category {
+    /// Any% and All Goals & Golds (split on both)
+    #[default]
+    Both,
+    /// Any% (Tokyo medal)
+    AnyPercent,
+    /// All Goals & Golds
+    AllGoalsAndGolds,
+}
+
+#[derive(Gui, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Thps12Category {
+    /// Any% (Roswell and Bullring medals)
+    #[default]
+    AnyPercent,
+    /// All Goals & Golds (split when ending the run that finishes both games)
+    AllGoalsAndGolds,
+}
+
+#[derive(Gui, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlcatrazTiming {
+    /// Load removed
+    #[default]
+    LoadRemoved,
+    /// Sum of run times
+    SumOfRuns,
+}
+
+#[derive(Gui, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MhpbCategory {
+    /// All medals collected
+    #[default]
+    AllMedals,
+    /// All gold medals collected
+    AllGolds,
+}
+
+// goals given by name or checksum (0x...), separated by commas or new lines
+// livesplit has no text box for autosplitter settings, so only a heading is shown and the list itself has to be added to the saved settings by hand
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GoalList {
+    pub goals: Vec<u32>,
+}
+
+impl GoalList {
+    pub fn parse(text: &str) -> Self {
+        let goals = text.split([',', '\n'])
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| match v.strip_prefix("0x").and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+                Some(v) => v,
+                None => checksum::checksum(v),
+            })
+            .collect();
+
+        Self { goals }
+    }
+}
+
+impl Widget for GoalList {
+    type Args = ();
+
+    fn register(key: &str, description: &str, args: Self::Args) -> Self {
+        asr::settings::gui::add_title(key, description, 1);
+
+        let mut result = Self::default();
+        result.update_from(&Map::load(), key, args);
+        result
+    }
+
+    fn update_from(&mut self, settings_map: &Map, key: &str, _args: Self::Args) {
+        *self = match settings_map.get(key).and_then(|v| v.get_string()) {
+            Some(v) => Self::parse(&v),
+            None => Self::default(),
+        };
+    }
+}
+
+// a pointer path and a length in bytes, e.g. "0x1674b8 -> 0x2cc0, 0x200".  the first offset is from the game's module, and every offset but
+// the last is dereferenced, the same as the pointer notes in the game modules.  like GoalList, this has to be added to the saved settings by hand
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub path: Vec<u64>,
+    pub len: u32,
+}
+
+impl MemoryRegion {
+    pub fn parse(text: &str) -> Option<Self> {
+        let (path, len) = text.split_once(',')?;
+
+        let path = path.split("->")
+            .map(parse_number)
+            .collect::<Option<Vec<u64>>>()?;
+
+        Some(Self {
+            path,
+            len: parse_number(len)? as u32,
+        })
+    }
+}
+
+impl fmt::Display for MemoryRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path: Vec<String> = self.path.iter().map(|v| format!("{:#x}", v)).collect();
+        write!(f, "{}", path.join(" -> "))
+    }
+}
+
+impl Widget for MemoryRegion {
+    type Args = ();
+
+    fn register(key: &str, description: &str, args: Self::Args) -> Self {
+        asr::settings::gui::add_title(key, description, 1);
+
+        let mut result = Self::default();
+        result.update_from(&Map::load(), key, args);
+        result
+    }
+
+    fn update_from(&mut self, settings_map: &Map, key: &str, _args: Self::Args) {
+        *self = settings_map.get(key)
+            .and_then(|v| v.get_string())
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default();
+    }
+}
+
+// hex with 0x, or decimal
+fn parse_number(text: &str) -> Option<u64> {
+    let text = text.trim();
+
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_memory_regions() {
+        let region = MemoryRegion::parse("0x1674b8 -> 0x2cc0, 0x200").unwrap();
+        assert_eq!(region, MemoryRegion { path: vec![0x1674b8, 0x2cc0], len: 0x200 });
+        assert_eq!(region.to_string(), "0x1674b8 -> 0x2cc0");
+
+        assert_eq!(MemoryRegion::parse("0x1674b8,16"), Some(MemoryRegion { path: vec![0x1674b8], len: 16 }));
+    }
+
+    #[test]
+    fn rejects_bad_memory_regions() {
+        assert_eq!(MemoryRegion::parse(""), None);
+        assert_eq!(MemoryRegion::parse("0x1674b8"), None);
+        assert_eq!(MemoryRegion::parse("0x1674b8 -> , 0x200"), None);
+        assert_eq!(MemoryRegion::parse("0x1674zz, 0x200"), None);
+        assert_eq!(MemoryRegion::parse("0x1674b8, lots"), None);
+    }
+}
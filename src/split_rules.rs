@@ -0,0 +1,200 @@
+// once-only splits.  each game lists its rules in order, and the engine remembers which ones have fired so a split can't happen twice in a run
+
+use crate::{settings::Settings, timer::Timer};
+
+pub struct SplitRule<S: 'static> {
+    pub name: &'static str,                     // logged as "<name>; splitting timer..."
+    pub enabled: fn(&Settings) -> bool,
+    pub condition: fn(&S, &S) -> bool,          // previous state, current state
+    pub rearm: Option<fn(&S, &S) -> bool>,      // lets a rule that already fired split again once this is true.  otherwise only a reset re-arms it
+    pub primed: Option<Primed<S>>,              // an extra condition that's only trusted after something else has been seen
+}
+
+// for values that are garbage until the game has set them up, e.g. comp results before the comp has started.  the engine remembers
+// whether `start` has been seen since `stop` was last true, and only checks `condition` in between
+pub struct Primed<S: 'static> {
+    pub start: fn(&S, &S) -> bool,
+    pub stop: fn(&S, &S) -> bool,
+    pub condition: fn(&S, &S) -> bool,
+}
+
+pub struct SplitRules<S: 'static> {
+    rules: &'static [SplitRule<S>],
+    fired: Vec<bool>,
+    primed: Vec<bool>,
+}
+
+impl<S> SplitRules<S> {
+    pub fn new(rules: &'static [SplitRule<S>]) -> Self {
+        Self {
+            rules,
+            fired: vec![false; rules.len()],
+            primed: vec![false; rules.len()],
+        }
+    }
+
+    // splits once for every enabled rule whose condition became true
+    pub fn update(&mut self, timer: &mut impl Timer, settings: &Settings, prev_state: &S, current_state: &S) {
+        for ((rule, fired), primed) in self.rules.iter().zip(self.fired.iter_mut()).zip(self.primed.iter_mut()) {
+            if let Some(p) = &rule.primed {
+                if !*primed && (p.start)(prev_state, current_state) {
+                    debug!("{}: primed", rule.name);
+                    *primed = true;
+                }
+
+                if *primed && (p.stop)(prev_state, current_state) {
+                    debug!("{}: no longer primed", rule.name);
+                    *primed = false;
+                }
+            }
+
+            if *fired {
+                if rule.rearm.is_some_and(|rearm| rearm(prev_state, current_state)) {
+                    *fired = false;
+                } else {
+                    continue;
+                }
+            }
+
+            let is_met = (rule.condition)(prev_state, current_state) || (*primed && rule.primed.as_ref().is_some_and(|p| (p.condition)(prev_state, current_state)));
+
+            if (rule.enabled)(settings) && is_met {
+                timer.split();
+                info!("{}; splitting timer...", rule.name);
+                *fired = true;
+            }
+        }
+    }
+
+    // re-arms every rule, for when the run is reset
+    pub fn reset(&mut self) {
+        self.fired.fill(false);
+        self.primed.fill(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::{ReplayTimer, TimerEvent};
+
+    // a counter, plus whether whatever the primed condition depends on has been set up
+    struct State {
+        count: u32,
+        ready: bool,
+    }
+
+    static RULES: &[SplitRule<State>] = &[
+        SplitRule {
+            name: "Reached 3",
+            enabled: |_| true,
+            condition: |_, s| s.count >= 3,
+            rearm: None,
+            primed: None,
+        },
+        SplitRule {
+            name: "Reached 5",
+            enabled: |_| true,
+            condition: |_, s| s.count >= 5,
+            rearm: Some(|_, s| s.count == 0),
+            primed: None,
+        },
+    ];
+
+    static PRIMED_RULES: &[SplitRule<State>] = &[
+        SplitRule {
+            name: "Reached 10, or 2 after setup",
+            enabled: |_| true,
+            condition: |_, s| s.count >= 10,
+            rearm: None,
+            primed: Some(Primed {
+                start: |_, s| s.ready && s.count == 0,
+                stop: |_, s| !s.ready,
+                condition: |_, s| s.count >= 2,
+            }),
+        },
+    ];
+
+    // runs the states through the rules in order and returns how many times the timer split on each one
+    fn run(rules: &mut SplitRules<State>, timer: &mut ReplayTimer, states: &[(u32, bool)]) -> Vec<usize> {
+        let settings = Settings::default();
+        let mut prev_state = State { count: 0, ready: false };
+        let mut result = Vec::new();
+
+        for &(count, ready) in states {
+            let current_state = State { count, ready };
+            let splits = timer.events.len();
+
+            rules.update(timer, &settings, &prev_state, &current_state);
+            result.push(timer.events[splits..].iter().filter(|(_, e)| *e == TimerEvent::Split).count());
+            prev_state = current_state;
+        }
+
+        result
+    }
+
+    fn started_timer() -> ReplayTimer {
+        let mut timer = ReplayTimer::new(None);
+        timer.start();
+        timer
+    }
+
+    #[test]
+    fn rules_fire_once() {
+        let mut rules = SplitRules::new(RULES);
+        let mut timer = started_timer();
+
+        assert_eq!(run(&mut rules, &mut timer, &[(1, false), (3, false), (4, false), (3, false), (5, false), (6, false)]), [0, 1, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn rearm_lets_a_rule_fire_again() {
+        let mut rules = SplitRules::new(RULES);
+        let mut timer = started_timer();
+
+        // only the second rule re-arms when the count goes back to 0
+        assert_eq!(run(&mut rules, &mut timer, &[(5, false), (0, false), (5, false)]), [2, 0, 1]);
+    }
+
+    #[test]
+    fn reset_rearms_every_rule() {
+        let mut rules = SplitRules::new(RULES);
+        let mut timer = started_timer();
+
+        assert_eq!(run(&mut rules, &mut timer, &[(5, false), (6, false)]), [2, 0]);
+        rules.reset();
+        assert_eq!(run(&mut rules, &mut timer, &[(6, false)]), [2]);
+    }
+
+    #[test]
+    fn primed_condition_waits_for_start() {
+        let mut rules = SplitRules::new(PRIMED_RULES);
+        let mut timer = started_timer();
+
+        // not set up yet, so reaching 2 doesn't count
+        assert_eq!(run(&mut rules, &mut timer, &[(2, true), (3, true)]), [0, 0]);
+
+        // set up, then losing the setup before reaching 2
+        assert_eq!(run(&mut rules, &mut timer, &[(0, true), (1, true), (2, false)]), [0, 0, 0]);
+
+        assert_eq!(run(&mut rules, &mut timer, &[(0, true), (2, true), (3, true)]), [0, 1, 0]);
+    }
+
+    #[test]
+    fn unprimed_rules_still_split_on_their_own_condition() {
+        let mut rules = SplitRules::new(PRIMED_RULES);
+        let mut timer = started_timer();
+
+        assert_eq!(run(&mut rules, &mut timer, &[(2, false), (10, false)]), [0, 1]);
+    }
+
+    #[test]
+    fn reset_forgets_priming() {
+        let mut rules = SplitRules::new(PRIMED_RULES);
+        let mut timer = started_timer();
+
+        assert_eq!(run(&mut rules, &mut timer, &[(0, true)]), [0]);
+        rules.reset();
+        assert_eq!(run(&mut rules, &mut timer, &[(2, true)]), [0]);
+    }
+}
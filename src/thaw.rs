@@ -1,115 +1,28 @@
 use std::{collections::HashSet, rc::Rc, cell::RefCell};
 
-use asr::{Address, Process, signature::Signature, timer::TimerState};
+use asr::{Address, Process, timer::TimerState};
+
+use crate::{attach, checksum, memory::MemorySource, research::Researcher, scan::{self, RootSignature}, script::{ScriptStruct, ScriptValue}, settings::Settings, split_rules::{SplitRule, SplitRules}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
 
 struct Offsets {
     skmodule: u32,
     load_counter: u32,
 }
 
-impl Offsets {
-    pub fn get(process: &Process, base_addr: Address, module_size: u64) -> Self {
-        let skmod_ptr = Signature::<25>::new("a1 ?? ?? ?? ?? 85 c0 75 3d 8a 4c 24 04 84 c9 74 35 6a 01 50 68 9c 00 00 00").scan_process_range(process, (base_addr, module_size)).unwrap() + 1;
-        let load_counter_ptr = Signature::<18>::new("84 c0 75 05 e8 ?? ?? ?? ?? a1 ?? ?? ?? ?? 85 c0 74 13").scan_process_range(process, (base_addr, module_size)).unwrap() + 10;
-
-        Offsets {
-            skmodule: match process.read::<u32>(skmod_ptr) {
-                Ok(v) => v - 0x400000,
-                Err(_) => 0,
-            },
-            load_counter: match process.read::<u32>(load_counter_ptr) {
-                Ok(v) => v - 0x400000,
-                Err(_) => 0,
-            },
-        }
-    }
-}
-
-struct ScriptStruct {
-    _unk: u32,
-    phead: u32,
-}
-
-impl ScriptStruct {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            _unk: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            phead: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-        }
-    }
-}
-
-/*
-// keeping this here in case it's needed for collectors edition (to check stats)...
-struct ScriptArray {
-    _unk: u32,
-    size: u32,
-    pdata: u32,
-}
-
-impl ScriptArray {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            _unk: match process.read_pointer_path32::<u32>(addr, &vec!(0x0 as u32)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            size: match process.read_pointer_path32::<u32>(addr, &vec!(0x4 as u32)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            pdata: match process.read_pointer_path32::<u32>(addr, &vec!(0x8 as u32)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-        }
-    }
-}
-*/
+static SKATE_MODULE_SIGNATURES: &[RootSignature] = &[
+    root_signature!(25, "a1 ?? ?? ?? ?? 85 c0 75 3d 8a 4c 24 04 84 c9 74 35 6a 01 50 68 9c 00 00 00", 1),
+];
 
-struct ScriptComponent {
-    _unk: u8,
-    ttype: u8,
-    _size: u16,
-    name: u32,
-    data: u32,
-    pnext: u32,
-}
+static LOAD_COUNTER_SIGNATURES: &[RootSignature] = &[
+    root_signature!(18, "84 c0 75 05 e8 ?? ?? ?? ?? a1 ?? ?? ?? ?? 85 c0 74 13", 10),
+];
 
-impl ScriptComponent {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            _unk: match process.read_pointer_path::<u8>(addr, asr::PointerSize::Bit32, &vec!(0x0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            ttype: match process.read_pointer_path::<u8>(addr, asr::PointerSize::Bit32, &vec!(0x1 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            _size: match process.read_pointer_path::<u16>(addr, asr::PointerSize::Bit32, &vec!(0x2 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            name: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            data: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x8 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            pnext: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0xc as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-        }
+impl Offsets {
+    pub fn get(process: &Process, base_addr: Address, module_size: u64) -> Option<Self> {
+        Some(Offsets {
+            skmodule: scan::resolve(process, base_addr, module_size, "the skate module", SKATE_MODULE_SIGNATURES)?,
+            load_counter: scan::resolve(process, base_addr, module_size, "the load counter", LOAD_COUNTER_SIGNATURES)?,
+        })
     }
 }
 
@@ -123,49 +36,42 @@ struct Goal {
     mode: Gamemode,
     complete: bool,
     _is_locked: bool,
-    _unk1: u32,
+    _has_seen: u32,
     unk2: u32,
-    _record: i32,
+    _win_record: i32,
 }
 
-fn read_goal(process: &Process, addr: u32) -> Option<Goal> {
-    let stru = ScriptStruct::read(process, addr);
-    let mut comp = stru.phead;
-
+fn read_goal(goal: &ScriptStruct) -> Option<Goal> {
     let mut mode = Gamemode::NONE;
     let mut complete = false;
     let mut _is_locked = false;
-    let mut _unk1 = 0;
+    let mut _has_seen = 0;
     let mut unk2 = 0;
-    let mut _record = 0;
+    let mut _win_record = 0;
 
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
-
-        if component.name == 0x49807745 {   // "hasBeaten"
-            if component.data == 1 {
+    for (name, value) in &goal.components {
+        if *name == checksum!("hasBeaten") {
+            if value.as_int() == Some(1) {
                 complete = true;
             }
-        } else if component.name == 0xc22a2b72 {
-            _record = component.data as i32;
-        } else if component.name == 0xd3e93882 {
-            if component.data == 1 {
+        } else if *name == checksum!("win_record") {
+            _win_record = value.as_int().unwrap_or(0);
+        } else if *name == checksum!("isLocked") {
+            if value.as_int() == Some(1) {
                 _is_locked = true;
             }
-        } else if component.name == 0x2206b1e7 {
-            _unk1 = component.data;
-        } else if component.name == 0x290a18e3 {
-            unk2 = component.data;
-        } else if component.ttype >> 1 == 0xd {
-            if component.data == 0x4da4937b {
+        } else if *name == checksum!("hasSeen") {
+            _has_seen = value.as_int().unwrap_or(0) as u32;
+        } else if *name == 0x290a18e3 {    // name unknown
+            unk2 = value.as_int().unwrap_or(0) as u32;
+        } else if let Some(checksum) = value.as_checksum() {
+            if checksum == checksum!("career") {
                 mode = Gamemode::CAREER;
             }
-            if component.data == 0x4C10DE52 {
+            if checksum == checksum!("classic") {
                 mode = Gamemode::CLASSIC;
             }
         }
-
-        comp = component.pnext;
     }
 
     if !matches!(mode, Gamemode::NONE) {
@@ -173,89 +79,73 @@ fn read_goal(process: &Process, addr: u32) -> Option<Goal> {
             mode,
             complete,
             _is_locked,
-            _unk1,
+            _has_seen,
             unk2,
-            _record,
+            _win_record,
         })
     } else {
         None
     }
 }
 
-#[derive(Debug, PartialEq, Eq)] 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 enum Difficulty {
+    #[default]
     UNKNOWN,
     EASY,
     NORMAL,
     SICK,
 }
 
-fn read_difficulty_levels(process: &Process, addr: u32, state: &mut State) {
-    let stru = ScriptStruct::read(process, addr);
-    let mut comp = stru.phead;
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
-
-        if component.name == 0x4da4937b {   // "career"
-            if component.data == 0 {
-                state.story_difficulty = Difficulty::EASY;
-            } else if component.data == 1 {
-                state.story_difficulty = Difficulty::NORMAL;
-            } else if component.data == 2 {
-                state.story_difficulty = Difficulty::SICK;
-            }
-        } else if component.name == 0x4C10DE52 {    // "classic"
-            if component.data == 0 {
-                state.classic_difficulty = Difficulty::NORMAL;
-            } else if component.data == 1 {
-                state.classic_difficulty = Difficulty::SICK;
-            }
+impl Difficulty {
+    fn from_id(id: u8) -> Self {
+        match id {
+            1 => Difficulty::EASY,
+            2 => Difficulty::NORMAL,
+            3 => Difficulty::SICK,
+            _ => Difficulty::UNKNOWN,
         }
-
-        comp = component.pnext;
     }
 }
 
-fn read_goal_params(process: &Process, addr: u32, state: &mut State) {
-    let stru = ScriptStruct::read(process, addr);
-    let mut comp = stru.phead;
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
+fn update_goal_flags(process: &impl MemorySource, addr: u32, goals: &mut Goals) -> Option<()> {
+    let flags = ScriptStruct::read(process, addr)?;
 
-        if component.name == 0xb13d98d3 {   // "difficulty_levels"
-            read_difficulty_levels(process, component.data, state);
-        }
+    match flags.get(&[checksum!("goalmanager_params"), checksum!("difficulty_levels"), checksum!("career")]).and_then(ScriptValue::as_int) {
+        Some(0) => goals.story_difficulty = Difficulty::EASY,
+        Some(1) => goals.story_difficulty = Difficulty::NORMAL,
+        Some(2) => goals.story_difficulty = Difficulty::SICK,
+        _ => {},
+    }
 
-        comp = component.pnext;
+    match flags.get(&[checksum!("goalmanager_params"), checksum!("difficulty_levels"), checksum!("classic")]).and_then(ScriptValue::as_int) {
+        Some(0) => goals.classic_difficulty = Difficulty::NORMAL,
+        Some(1) => goals.classic_difficulty = Difficulty::SICK,
+        _ => {},
     }
-}
 
-fn update_goal_flags(process: &Process, addr: u32, state: &mut State) {
-    let stru = ScriptStruct::read(process, addr);
-    let mut comp = stru.phead;
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
-
-        if component.name == 0x23d4170a {   // "goalmanager_params"
-            read_goal_params(process, component.data, state);
-        } else if component.ttype >> 1 == 0xa {
-            if let Some(goal) = read_goal(process, component.data) {
-                if goal.complete && goal.unk2 == 1 && state.completed_goals.borrow_mut().insert(component.name) {
-                    if matches!(goal.mode, Gamemode::CAREER) {
-                        state.story_goals += 1;
-                    } else {
-                        state.classic_goals += 1;
-                    }
-                }
+    // every other struct is a goal's flags
+    for (name, value) in &flags.components {
+        if *name == checksum!("goalmanager_params") {
+            continue;
+        }
 
-                //if goal.is_locked {
-                //    state.locked_goals.borrow_mut().insert(component.name);
-                //}
+        if let Some(goal) = value.as_struct().and_then(read_goal) {
+            if goal.complete && goal.unk2 == 1 && goals.completed.insert(*name) {
+                if matches!(goal.mode, Gamemode::CAREER) {
+                    goals.story += 1;
+                } else {
+                    goals.classic += 1;
+                }
             }
-        }
 
-        comp = component.pnext;
+            //if goal.is_locked {
+            //    state.locked_goals.borrow_mut().insert(*name);
+            //}
+        }
     }
+
+    Some(())
 }
 
 struct GoalListNode {
@@ -265,21 +155,10 @@ struct GoalListNode {
 }
 
 impl GoalListNode {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            name: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            pgoal: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            pnext: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x8 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-        }
+    pub fn read(process: &impl MemorySource, addr: u32) -> Option<Self> {
+        let [name, pgoal, pnext] = process.read::<[u32; 3]>(addr).ok()?;
+
+        Some(Self { name, pgoal, pnext })
     }
 }
 
@@ -292,29 +171,10 @@ struct GoalList {
 }
 
 impl GoalList {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            num: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            phead: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            _ptail: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x8 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            _pcurrent: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0xc as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            _idx: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x10 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-        }
+    pub fn read(process: &impl MemorySource, addr: u32) -> Option<Self> {
+        let [num, phead, _ptail, _pcurrent, _idx] = process.read::<[u32; 5]>(addr).ok()?;
+
+        Some(Self { num, phead, _ptail, _pcurrent, _idx })
     }
 }
 
@@ -331,49 +191,100 @@ fn is_classic_level(id: u32) -> bool {
     CLASSIC_LEVELS.contains(&id)
 }
 
-fn update_goals(process: &Process, addr: u32, state: &mut State) {
-    let goal_list = GoalList::read(process, addr);
+// the goal's parent pointer and flags, read together
+const GOAL_PARENT: usize = 0x28;
+const GOAL_FLAGS: usize = 0x68;
+
+// `known` is the last good read, so the parent of a goal that was already complete doesn't need looking up again
+fn update_goals(process: &impl MemorySource, addr: u32, level_id: u32, known: &HashSet<u32>, goals: &mut Goals) -> Option<()> {
+    let goal_list = GoalList::read(process, addr)?;
 
     let mut pnode = goal_list.phead;
     for _ in 0..goal_list.num {
-        let node = GoalListNode::read(process, pnode);
-        if let Ok(flags) = process.read_pointer_path::<u32>(node.pgoal, asr::PointerSize::Bit32, &vec!(0x68 as u64)) {
-            if flags & 0x4 != 0 {
-                if !state.completed_goals.borrow().contains(&node.name) {
-                    let mut is_leaf = false;
-
-                    if let Ok(parent) = process.read_pointer_path::<u32>(node.pgoal, asr::PointerSize::Bit32, &vec!(0x28 as u64, 0x0 as u64))  {
-                        if parent == 0 {
-                            state.completed_goals.borrow_mut().insert(node.name);
-                            is_leaf = true;
-                        }
-                    } else {
-                        state.completed_goals.borrow_mut().insert(node.name);
-                        is_leaf = true;
-                    }
-
-                    if is_leaf {
-                        if !is_classic_level(state.level_id) {
-                            state.story_goals += 1;
-                        } else {
-                            state.classic_goals += 1;
-                        }
-                    }
+        let node = GoalListNode::read(process, pnode)?;
+        let goal = process.read_block(node.pgoal, GOAL_FLAGS + 4).ok()?;
+        let flags = goal.get::<u32>(GOAL_FLAGS)?;
+
+        if flags & 0x4 != 0 && !goals.completed.contains(&node.name) {
+            // only goals without a parent count
+            let is_leaf = known.contains(&node.name) || match goal.get::<u32>(GOAL_PARENT).map(|v| process.read::<u32>(v)) {
+                Some(Ok(parent)) => parent == 0,
+                _ => true,
+            };
+
+            if is_leaf {
+                goals.completed.insert(node.name);
+                if !is_classic_level(level_id) {
+                    goals.story += 1;
+                } else {
+                    goals.classic += 1;
                 }
-            } //else if flags & 0x20 != 0 {
-            //    state.locked_goals.borrow_mut().insert(node.name);
-            //}
-
-            if flags & 0x100 != 0 {
-                state.run_is_active = true;
             }
+        } //else if flags & 0x20 != 0 {
+        //    state.locked_goals.borrow_mut().insert(node.name);
+        //}
+
+        if flags & 0x100 != 0 {
+            goals.run_is_active = true;
         }
 
         pnode = node.pnext;
     }
+
+    Some(())
 }
 
-struct State {
+// everything read from the goal manager.  it's only kept when every part of it reads, since a partial read looks like goals being lost
+#[derive(Clone, Default, PartialEq)]
+struct Goals {
+    completed: HashSet<u32>,
+    story: u32,
+    classic: u32,
+    run_is_active: bool,
+    story_difficulty: Difficulty,
+    classic_difficulty: Difficulty,
+}
+
+fn read_goals(process: &impl MemorySource, goal_manager: u32, level_id: u32, known: &HashSet<u32>) -> Option<Goals> {
+    let mut goals = Goals::default();
+
+    // no goal manager means there's no career loaded, so there's nothing to read
+    if goal_manager == 0 {
+        return Some(goals);
+    }
+
+    update_goals(process, goal_manager, level_id, known, &mut goals)?;
+
+    let flags = process.read::<u32>(goal_manager + 0x14).ok()?;
+    update_goal_flags(process, flags, &mut goals)?;
+
+    Some(goals)
+}
+
+pub struct Watchers {
+    skmodule: MemoryWatcher<u32>,
+    level_id: MemoryWatcher<u32>,
+    goal_manager: MemoryWatcher<u32>,
+    session_goals: MemoryWatcher<u32>,
+    load_counter: MemoryWatcher<u32>,
+    goals: Watcher<Goals>,
+}
+
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
+            // the level and everything goal related hang off the skate module
+            skmodule: MemoryWatcher::new(&[offsets.skmodule as u64]),
+            level_id: MemoryWatcher::new(&[0x20, 0xb0]),
+            goal_manager: MemoryWatcher::new(&[0x78]),
+            session_goals: MemoryWatcher::new(&[0x38]),
+            load_counter: MemoryWatcher::new(&[offsets.load_counter as u64]),
+            goals: Watcher::holding(),
+        }
+    }
+}
+
+pub struct State {
     level_id: u32,
     load_counter: u32,
     story_goals: u32,
@@ -387,41 +298,38 @@ struct State {
 }
 
 impl State {
-    pub fn update(process: &Process, base_addr: Address, offsets: &Offsets) -> Self {
-        let mut result = Self {
-            level_id: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(offsets.skmodule as u64, 0x20 as u64, 0xb0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            load_counter: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(offsets.load_counter as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            story_goals: 0,
-            classic_goals: 0,
-            run_is_active: false,
-            completed_goals: Rc::new(RefCell::new(HashSet::new())),
-            locked_goals: Rc::new(RefCell::new(HashSet::new())),
-            classic_difficulty: Difficulty::UNKNOWN,
-            story_difficulty: Difficulty::UNKNOWN,
+    pub fn update(process: &impl MemorySource, base_addr: Address, watchers: &mut Watchers) -> Self {
+        let skmodule = Address::new(*watchers.skmodule.update(process, base_addr).current() as u64);
+        let level_id = *watchers.level_id.update(process, skmodule).current();
+        let goal_manager = *watchers.goal_manager.update(process, skmodule).current();
 
-            session_goals: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(offsets.skmodule as u64, 0x78 as u64, 0x38 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-        };
+        let goals = read_goals(process, goal_manager, level_id, &watchers.goals.current().completed);
+        let goals = watchers.goals.update(goals);
 
-        if let Ok(addr) = process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(offsets.skmodule as u64, 0x78 as u64)) {
-            update_goals(process, addr, &mut result);
+        for goal in goals.current().completed.difference(&goals.old().completed) {
+            debug!("Goal completed: {}", checksum::describe(*goal));
         }
 
-        if let Ok(addr) = process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(offsets.skmodule as u64, 0x78 as u64, 0x14 as u64)) {
-            update_goal_flags(process, addr, &mut result);
+        let goals = goals.current();
+
+        Self {
+            level_id,
+            load_counter: *watchers.load_counter.update(process, base_addr).current(),
+            story_goals: goals.story,
+            classic_goals: goals.classic,
+            run_is_active: goals.run_is_active,
+            completed_goals: Rc::new(RefCell::new(goals.completed.clone())),
+            locked_goals: Rc::new(RefCell::new(HashSet::new())),
+            classic_difficulty: goals.classic_difficulty,
+            story_difficulty: goals.story_difficulty,
+            session_goals: *watchers.session_goals.update(process, Address::new(goal_manager as u64)).current(),
         }
+    }
 
-        return result;
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Goals", self.story_goals);
+        variables.set("Classic Goals", self.classic_goals);
     }
 }
 
@@ -441,265 +349,296 @@ static CASINO_GOALS: [u32; 7] = [
     0xd67b166d,
 ];
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THAW!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("level", self.level_id);
+        w.field("load_counter", self.load_counter);
+        w.field("story_goals", self.story_goals);
+        w.field("classic_goals", self.classic_goals);
+        w.set_field("completed", self.completed_goals.borrow().iter());
+        w.set_field("locked", self.locked_goals.borrow().iter());
+        w.field("run_active", self.run_is_active);
+        w.field("session_goals", self.session_goals);
+        w.field("story_difficulty", self.story_difficulty as u8);
+        w.field("classic_difficulty", self.classic_difficulty as u8);
+    }
+
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            level_id: r.field("level")?,
+            load_counter: r.field("load_counter")?,
+            story_goals: r.field("story_goals")?,
+            classic_goals: r.field("classic_goals")?,
+            completed_goals: Rc::new(RefCell::new(r.set_field("completed")?)),
+            locked_goals: Rc::new(RefCell::new(r.set_field("locked")?)),
+            run_is_active: r.field("run_active")?,
+            session_goals: r.field("session_goals")?,
+            story_difficulty: Difficulty::from_id(r.field("story_difficulty")?),
+            classic_difficulty: Difficulty::from_id(r.field("classic_difficulty")?),
+        })
+    }
+}
 
-    let base_addr = process.get_module_address(process_name).unwrap();
-    let module_size = process.get_module_size(process_name).unwrap();
+fn goal_completed(prev_state: &State, current_state: &State, goal: u32) -> bool {
+    Watcher::between(prev_state.completed_goals.borrow().contains(&goal), current_state.completed_goals.borrow().contains(&goal)).changed_to(&true)
+}
 
-    let offsets = Offsets::get(process, base_addr, module_size);
+// easier difficulties count as some of the goals needed to unlock the ranch and final goal
+fn unlock_progress(completed_goals: &HashSet<u32>, difficulty: Difficulty, goals: &[u32], easy_bonus: u32, normal_bonus: u32) -> u32 {
+    let bonus = match difficulty {
+        Difficulty::UNKNOWN => 0,
+        Difficulty::EASY => easy_bonus,
+        Difficulty::NORMAL => normal_bonus,
+        Difficulty::SICK => 0,
+    };
 
-    let mut prev_state = State::update(process, base_addr, &offsets);
+    bonus + goals.iter().filter(|goal| completed_goals.contains(*goal)).count() as u32
+}
 
-    let mut is_paused = false;
-    let mut mode = Gamemode::NONE;
-    let mut story_flags = [
-        false, // beverly hills opened OR beverly hills visited(?) (complete 0x7a446a0a OR level == 2)
-        false, // ranch unlocked (finished challenges)
-        false, // downtown unlocked (falling down goal) (complete 0x8f0cd51c)
-        false, // amjam unlocked (last zen goal) (complete 0x607572ca)
-        false, // amjam complete (amjam goal) (complete 0x3a800176)
-        false, // santa monica unlocked (beat daewon) (complete 0xb9415865)
-        false, // oil rig unlocked (paid oil rig) (complete 0xea9af00c)
-        false, // oil rig route unlocked (chopper goal) (complete 0xd32df1bb)
-        false, // east la unlocked (black widowz goal) (complete 0xda77ca92)
-        false, // pro goals started (joey b baggie) (complete 0x97e3e2cf)
-        false, // casino unlocked (paid taco truck) (complete 0x82510d90)
-        false, // final goal unlocked (completed x casino goals) 
-        false, // final goal complete (complete 0x99156422)
-    ];
+// all three of boone, dave and murphy's goals on sick, two on normal, one on easy
+fn is_ranch_unlocked(completed_goals: &HashSet<u32>, difficulty: Difficulty) -> bool {
+    unlock_progress(completed_goals, difficulty, &RANCH_GOALS, 2, 1) >= 3
+}
 
-    loop {
-        // update vars
-        let current_state = State::update(process, base_addr, &offsets);
-
-        // sometimes the read of goal flags fails and causes a bunch of goals to re-complete  
-        // to avoid that, just copy previous progress if session goals hasn't changed but the others have
-        if (current_state.story_goals != prev_state.story_goals || 
-            current_state.classic_goals != prev_state.classic_goals) &&
-            current_state.session_goals == prev_state.session_goals {
-
-            asr::print_message(&format!("WARNING: goal flags failed to read!"));
-            
-            for i in prev_state.completed_goals.borrow().iter() {
-                current_state.completed_goals.borrow_mut().insert(*i);
-            }
-            for i in prev_state.locked_goals.borrow().iter() {
-                current_state.locked_goals.borrow_mut().insert(*i);
-            }
-        }
+// all seven casino goals on sick, five on normal, four on easy
+fn is_final_goal_unlocked(completed_goals: &HashSet<u32>, difficulty: Difficulty) -> bool {
+    unlock_progress(completed_goals, difficulty, &CASINO_GOALS, 3, 2) >= 7
+}
 
-        let mut completed_goals = Vec::new();
-        for i in current_state.completed_goals.borrow().iter() {
-            if !prev_state.completed_goals.borrow().contains(&i) {
-                completed_goals.push(*i);
-            }
+const FINAL_GOAL: u32 = 0x99156422;
+
+// a custom goal list replaces the milestones
+fn milestones_enabled(settings: &Settings) -> bool {
+    settings.thaw_split_milestones && settings.thaw_story_goals.goals.is_empty()
+}
+
+static STORY_RULES: &[SplitRule<State>] = &[
+    SplitRule {
+        name: "Unlocked or skipped to Beverly Hills",
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0x7a446a0a) || (s.level_id == 2 && prev.level_id != 2),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked Skate Ranch",
+        enabled: milestones_enabled,
+        condition: |_, s| is_ranch_unlocked(&s.completed_goals.borrow(), s.story_difficulty),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked Downtown",    // falling down goal
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0x8f0cd51c),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked Amjam",    // last zen goal
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0x607572ca),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Won Amjam",    // amjam goal
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0x3a800176),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked Santa Monica",    // beat daewon
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0xb9415865),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked Oil Rig",    // paid oil rig
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0xea9af00c),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Finished Oil Rig",    // chopper goal
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0xd32df1bb),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked East LA",    // black widowz goal
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0xda77ca92),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Pro Goals",    // joey b baggie
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0x97e3e2cf),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked Casino",    // paid taco truck
+        enabled: milestones_enabled,
+        condition: |prev, s| goal_completed(prev, s, 0x82510d90),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Unlocked final goal",
+        enabled: milestones_enabled,
+        condition: |_, s| is_final_goal_unlocked(&s.completed_goals.borrow(), s.story_difficulty),
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Completed final goal",
+        enabled: |s| s.thaw_split_final && !s.thaw_story_goals.goals.contains(&FINAL_GOAL),     // a list with the final goal in it splits on it in order
+        condition: |prev, s| goal_completed(prev, s, FINAL_GOAL),
+        rearm: None,
+        primed: None,
+    },
+];
+
+// splits on the goals in the runner's list one at a time, in list order.  a goal completed before its turn splits as soon as the goals before it have
+fn split_goal_list(next_goal: &mut usize, timer: &mut impl Timer, settings: &Settings, current_state: &State) {
+    let Some(goal) = settings.thaw_story_goals.goals.get(*next_goal) else {
+        return;
+    };
+
+    if current_state.completed_goals.borrow().contains(goal) {
+        timer.split();
+        info!("Completed {}; splitting timer...", checksum::describe(*goal));
+        *next_goal += 1;
+    }
+}
+
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+    is_paused: bool,
+    mode: Gamemode,
+    story: SplitRules<State>,
+    next_goal: usize,    // position in the runner's goal list
+}
+
+impl GameSplitter for Splitter {
+    type State = State;
+
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+            is_paused: false,
+            // series runs go straight into story mode since the timer is already running
+            mode: if continuing_series {
+                Gamemode::CAREER
+            } else {
+                Gamemode::NONE
+            },
+            story: SplitRules::new(STORY_RULES),
+            next_goal: 0,
         }
+    }
+
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, current_state: State) {
+        let prev_state = &self.prev_state;
 
         /*
         if current_state.level_id == prev_state.level_id && current_state.story_goals >= prev_state.story_goals {
             for i in prev_state.locked_goals.borrow().iter() {
                 if !current_state.locked_goals.borrow().contains(&i) {
-                    asr::print_message(&format!("UNLOCKED GOAL {:#010x}!", i));
+                    info!("UNLOCKED GOAL {:#010x}!", i);
                 }
             }
         }
         */
 
-        match asr::timer::state() {
+        let level_id = Watcher::between(prev_state.level_id, current_state.level_id);
+
+        match timer.state() {
             TimerState::NotRunning => {
-                story_flags.fill(false);
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+
+                self.story.reset();
+                self.next_goal = 0;
 
-                if is_paused {
-                    is_paused = false;
-                    asr::timer::resume_game_time();
-                    asr::print_message(format!("Done loading!").as_str());
+                if self.is_paused {
+                    self.is_paused = false;
+                    timer.resume_game_time();
+                    debug!("Done loading!");
                 }
 
                 // start story when level == 1, no goals are complete, and goal is active
-                if current_state.level_id == 1 && current_state.story_goals == 0 && current_state.run_is_active {
-                    asr::timer::start();
-                    asr::print_message(&format!("Starting timer for Story..."));
-                    mode = Gamemode::CAREER;
+                if settings.thaw_start_story && current_state.level_id == 1 && current_state.story_goals == 0 && current_state.run_is_active {
+                    timer.start();
+                    info!("Starting timer for Story...");
+                    self.mode = Gamemode::CAREER;
                 }
 
                 // start classic when level == 11 from menu
-                if current_state.level_id == 11 && prev_state.level_id == 0 && current_state.classic_goals == 0 {
-                    asr::timer::start();
-                    asr::print_message(&format!("Starting timer for Classic..."));
-                    mode = Gamemode::CLASSIC;
+                if settings.thaw_start_classic && *level_id.old() == 0 && level_id.changed_to(&11) && current_state.classic_goals == 0 {
+                    timer.start();
+                    info!("Starting timer for Classic...");
+                    self.mode = Gamemode::CLASSIC;
                 }
             },
             TimerState::Paused | TimerState::Running => {
-                match mode {
+                match self.mode {
                     Gamemode::NONE => {},
                     Gamemode::CAREER => {
-                        if is_paused {
-                            is_paused = false;
-                            asr::timer::resume_game_time();
-                            asr::print_message(format!("Done loading!").as_str());
-                        }
-
-                        // split on unlock or visit beverly hills
-                        if !story_flags[0] && (completed_goals.contains(&0x7a446a0a) || (current_state.level_id == 2 && prev_state.level_id != 2)) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked or skipped to Beverly Hills; splitting timer...").as_str());
-                            story_flags[0] = true;
-                        }
-
-                        // split on unlock or visit beverly hills
-                        if !story_flags[1] {
-                            // calculate ranch unlock conditions
-                            let mut ranch_conditions = 0;
-                            ranch_conditions += match current_state.story_difficulty {
-                                Difficulty::UNKNOWN => 0,
-                                Difficulty::EASY => 2,
-                                Difficulty::NORMAL => 1,
-                                Difficulty::SICK => 0,
-                            };
-
-                            for i in RANCH_GOALS {
-                                if current_state.completed_goals.borrow().contains(&i) {
-                                    ranch_conditions += 1;
-                                }
-                            }
-
-                            if ranch_conditions >= 3 {
-                                asr::timer::split();
-                                asr::print_message(format!("Unlocked Skate Ranch; splitting timer...").as_str());
-                                story_flags[1] = true;
-                            }
-                        }
-
-                        // split on unlock downtown
-                        if !story_flags[2] && completed_goals.contains(&0x8f0cd51c) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked Downtown; splitting timer...").as_str());
-                            story_flags[2] = true;
-                        }
-
-                        // split on unlock amjam
-                        if !story_flags[3] && completed_goals.contains(&0x607572ca) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked Amjam; splitting timer...").as_str());
-                            story_flags[3] = true;
-                        }
-
-                        // split on win amjam
-                        if !story_flags[4] && completed_goals.contains(&0x3a800176) {
-                            asr::timer::split();
-                            asr::print_message(format!("Won Amjam; splitting timer...").as_str());
-                            story_flags[4] = true;
-                        }
-
-                        // split on unlock santa monica
-                        if !story_flags[5] && completed_goals.contains(&0xb9415865) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked Santa Monica; splitting timer...").as_str());
-                            story_flags[5] = true;
+                        if self.is_paused {
+                            self.is_paused = false;
+                            timer.resume_game_time();
+                            debug!("Done loading!");
                         }
 
-                        // split on unlock oil rig
-                        if !story_flags[6] && completed_goals.contains(&0xea9af00c) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked Oil Rig; splitting timer...").as_str());
-                            story_flags[6] = true;
-                        }
-
-                        // split on finish oil rig
-                        if !story_flags[7] && completed_goals.contains(&0xd32df1bb) {
-                            asr::timer::split();
-                            asr::print_message(format!("Finished Oil Rig; splitting timer...").as_str());
-                            story_flags[7] = true;
-                        }
-
-                        // split on unlock east la
-                        if !story_flags[8] && completed_goals.contains(&0xda77ca92) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked East LA; splitting timer...").as_str());
-                            story_flags[8] = true;
-                        }
-
-                        // split on unlock santa monica
-                        if !story_flags[9] && completed_goals.contains(&0x97e3e2cf) {
-                            asr::timer::split();
-                            asr::print_message(format!("Started Pro Goals; splitting timer...").as_str());
-                            story_flags[9] = true;
-                        }
-
-                        // split on unlock casino
-                        if !story_flags[10] && completed_goals.contains(&0x82510d90) {
-                            asr::timer::split();
-                            asr::print_message(format!("Unlocked Casino; splitting timer...").as_str());
-                            story_flags[10] = true;
-                        }
-
-                        // split on unlock final goal
-                        if !story_flags[11] {
-                            // calculate ranch unlock conditions
-                            let mut unlock_conditions = 0;
-                            unlock_conditions += match current_state.story_difficulty {
-                                Difficulty::UNKNOWN => 0,
-                                Difficulty::EASY => 3,
-                                Difficulty::NORMAL => 2,
-                                Difficulty::SICK => 0,
-                            };
-
-                            for i in CASINO_GOALS {
-                                if current_state.completed_goals.borrow().contains(&i) {
-                                    unlock_conditions += 1;
-                                }
-                            }
-
-                            if unlock_conditions >= 7 {
-                                asr::timer::split();
-                                asr::print_message(format!("Unlocked final goal; splitting timer...").as_str());
-                                story_flags[11] = true;
-                            }
-                        }
-
-                        // split on final goal
-                        if !story_flags[12] && completed_goals.contains(&0x99156422) {
-                            asr::timer::split();
-                            asr::print_message(format!("Completed final goal; splitting timer...").as_str());
-                            story_flags[12] = true;
-                        }
+                        // a custom goal list replaces the milestones, but not the final split
+                        self.story.update(timer, settings, prev_state, &current_state);
+                        split_goal_list(&mut self.next_goal, timer, settings, &current_state);
 
                         // reset on 0 goals complete on menu
-                        if current_state.level_id == 0 && current_state.story_goals == 0 {
-                            asr::timer::reset();
-                            asr::print_message(format!("Resetting timer...").as_str());
-                            story_flags.fill(false);
+                        if settings.thaw_reset && !self.continuing_series && current_state.level_id == 0 && current_state.story_goals == 0 {
+                            timer.reset();
+                            info!("Resetting timer...");
+                            self.story.reset();
+                            self.next_goal = 0;
                         }
                     },
                     Gamemode::CLASSIC => {
-                        if current_state.load_counter > 0 && !is_paused {
-                            is_paused = true;
-                            asr::timer::pause_game_time();
-                            asr::print_message(format!("Starting Load... {}", current_state.load_counter).as_str());
-                        } else if current_state.load_counter == 0 && is_paused {
-                            is_paused = false;
-                            asr::timer::resume_game_time();
-                            asr::print_message(format!("Done loading!").as_str());
+                        if current_state.load_counter > 0 && !self.is_paused {
+                            self.is_paused = true;
+                            timer.pause_game_time();
+                            debug!("Starting Load... {}", current_state.load_counter);
+                        } else if current_state.load_counter == 0 && self.is_paused {
+                            self.is_paused = false;
+                            timer.resume_game_time();
+                            debug!("Done loading!");
                         }
 
                         // split on level changes (except main menu)
-                        if current_state.level_id != prev_state.level_id && current_state.level_id != 0 {
-                            asr::timer::split();
-                            asr::print_message(format!("Changed level; splitting timer...").as_str());
+                        if settings.thaw_split_classic_level && level_id.changed() && current_state.level_id != 0 {
+                            timer.split();
+                            info!("Changed level; splitting timer...");
                         }
 
                         // split on end run when 51 goals are complete
-                        if current_state.classic_goals >= 51 && !current_state.run_is_active && prev_state.run_is_active {  // FIXME: goes off when restarting
-                            asr::timer::split();
-                            asr::print_message(format!("Classic complete; splitting timer...").as_str());
+                        if settings.thaw_split_classic_end && current_state.classic_goals >= 51 && Watcher::between(prev_state.run_is_active, current_state.run_is_active).changed_to(&false) {  // FIXME: goes off when restarting
+                            timer.split();
+                            info!("Classic complete; splitting timer...");
                         }
 
                         // reset on 0 goals complete on menu
-                        if current_state.level_id == 0 && current_state.classic_goals == 0 {
-                            asr::timer::reset();
-                            asr::print_message(format!("Resetting timer...").as_str());
+                        if settings.thaw_reset && !self.continuing_series && current_state.level_id == 0 && current_state.classic_goals == 0 {
+                            timer.reset();
+                            info!("Resetting timer...");
                         }
                     },
                 }
@@ -709,8 +648,193 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THAW!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let (base_addr, module_size) = attach::module_range(process, process_name).await;
+    let offsets = attach::retry("THAW offsets", || Offsets::get(process, base_addr, module_size)).await;
+
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THAW");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
+
+    loop {
+        settings.refresh();
+
+        // update vars
+        let current_state = State::update(process, base_addr, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use asr::PointerSize;
+
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
+
+    const DIFFICULTIES: [Difficulty; 4] = [Difficulty::UNKNOWN, Difficulty::EASY, Difficulty::NORMAL, Difficulty::SICK];
+
+    // the first `count` goals of the list, plus a goal from somewhere else that shouldn't count towards anything
+    fn completed(goals: &[u32], count: usize) -> HashSet<u32> {
+        goals.iter().take(count).copied().chain([0x7a446a0a]).collect()
+    }
+
+    // how many of the listed goals each difficulty needs, as counted in game.  an unknown difficulty is treated like sick
+    const RANCH_GOALS_NEEDED: [(Difficulty, usize); 4] = [(Difficulty::UNKNOWN, 3), (Difficulty::EASY, 1), (Difficulty::NORMAL, 2), (Difficulty::SICK, 3)];
+    const CASINO_GOALS_NEEDED: [(Difficulty, usize); 4] = [(Difficulty::UNKNOWN, 7), (Difficulty::EASY, 4), (Difficulty::NORMAL, 5), (Difficulty::SICK, 7)];
+
+    #[test]
+    fn ranch_unlocks_with_the_right_number_of_goals() {
+        for (difficulty, needed) in RANCH_GOALS_NEEDED {
+            for count in 0..=RANCH_GOALS.len() {
+                assert_eq!(is_ranch_unlocked(&completed(&RANCH_GOALS, count), difficulty), count >= needed, "{:?} with {} goals", difficulty, count);
+            }
+        }
+    }
+
+    #[test]
+    fn final_goal_unlocks_with_the_right_number_of_goals() {
+        for (difficulty, needed) in CASINO_GOALS_NEEDED {
+            for count in 0..=CASINO_GOALS.len() {
+                assert_eq!(is_final_goal_unlocked(&completed(&CASINO_GOALS, count), difficulty), count >= needed, "{:?} with {} goals", difficulty, count);
+            }
+        }
+    }
+
+    #[test]
+    fn easy_ranch_needs_one_goal() {
+        assert!(!is_ranch_unlocked(&HashSet::new(), Difficulty::EASY));
+        assert!(is_ranch_unlocked(&HashSet::from([RANCH_GOALS[2]]), Difficulty::EASY));
+    }
+
+    #[test]
+    fn sick_needs_every_goal() {
+        assert!(!is_ranch_unlocked(&completed(&RANCH_GOALS, 2), Difficulty::SICK));
+        assert!(is_ranch_unlocked(&completed(&RANCH_GOALS, 3), Difficulty::SICK));
+        assert!(!is_final_goal_unlocked(&completed(&CASINO_GOALS, 6), Difficulty::SICK));
+        assert!(is_final_goal_unlocked(&completed(&CASINO_GOALS, 7), Difficulty::SICK));
+    }
+
+    #[test]
+    fn goals_from_the_other_list_dont_count() {
+        for difficulty in DIFFICULTIES {
+            assert!(!is_ranch_unlocked(&completed(&CASINO_GOALS, CASINO_GOALS.len()), difficulty));
+            assert!(!is_final_goal_unlocked(&completed(&RANCH_GOALS, RANCH_GOALS.len()), difficulty));
+        }
+    }
+
+    const BASE: u32 = 0x400000;
+    const OFFSETS: Offsets = Offsets { skmodule: 0x1000, load_counter: 0x2000 };
+    const SKMODULE: u32 = 0x20000000;
+    const GOAL_MANAGER: u32 = 0x20010000;
+    const NODES: u32 = 0x20020000;
+    const GOALS: u32 = 0x20030000;
+    const FLAGS: u32 = 0x20040000;
+
+    const BEVERLY_HILLS: u32 = 0x7a446a0a;
+    const OTHER_GOAL: u32 = 0x12345678;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+    }
+
+    impl Game {
+        // starts on the first level of a new story, with a goal list holding the beverly hills goal and one that's running
+        fn new() -> Self {
+            let mut memory = MockMemory::new();
+            memory.write(BASE + OFFSETS.skmodule, SKMODULE);
+            memory.write_pointer_path(Address::new(SKMODULE as u64), PointerSize::Bit32, &[0x20, 0xb0], 1u32);
+            memory.write(SKMODULE + 0x78, GOAL_MANAGER);
+            memory.write(GOAL_MANAGER, [2, NODES, 0, 0, 0]);
+            memory.write(GOAL_MANAGER + 0x14, FLAGS);
+            memory.write(GOAL_MANAGER + 0x38, 0u32);
+            memory.write(FLAGS + 4, 0u32);
+
+            for (i, name) in [BEVERLY_HILLS, OTHER_GOAL].into_iter().enumerate() {
+                let node = NODES + i as u32 * 0x10;
+                let goal = GOALS + i as u32 * 0x100;
+                let next = if i == 0 { node + 0x10 } else { 0 };
+
+                memory.write(node, [name, goal, next]);
+                memory.write_bytes(goal, &[0; GOAL_FLAGS + 4]);
+            }
+
+            memory.write(GOALS + 0x100 + GOAL_FLAGS as u32, 0x100u32);
+
+            let mut watchers = Watchers::new(&OFFSETS);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE as u64), &mut watchers), false);
+
+            Self { memory, watchers, splitter, timer: ReplayTimer::new(None) }
+        }
+
+        fn complete(&mut self, goal: usize) {
+            self.memory.write(GOALS + goal as u32 * 0x100 + GOAL_FLAGS as u32, 0x4u32);
+        }
+
+        fn read(&mut self) -> State {
+            State::update(&self.memory, Address::new(BASE as u64), &mut self.watchers)
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = self.read();
+            self.splitter.update(&mut self.timer, &Settings::default(), state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_story_on_the_first_goal_and_splits_on_milestones() {
+        let mut game = Game::new();
+        assert_eq!(game.tick(), [Start]);
+
+        game.complete(0);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn goals_are_kept_through_a_failed_read() {
+        let mut game = Game::new();
+        game.complete(0);
+        assert_eq!(game.read().story_goals, 1);
+
+        // the goal list is being rebuilt, so its second node points nowhere
+        game.memory.write(NODES, [BEVERLY_HILLS, GOALS, 0x30000000]);
+        let state = game.read();
+        assert_eq!(state.story_goals, 1);
+        assert!(state.completed_goals.borrow().contains(&BEVERLY_HILLS));
+        assert!(state.run_is_active);
+    }
+
+    #[test]
+    fn no_goal_manager_means_no_goals() {
+        let mut game = Game::new();
+        game.complete(0);
+        assert_eq!(game.read().story_goals, 1);
+
+        game.memory.write(SKMODULE + 0x78, 0u32);
+        assert_eq!(game.read().story_goals, 0);
+    }
+}
@@ -1,12 +1,26 @@
 use asr::{timer::TimerState, Process};
 
-use crate::alcatraz_utils;
+use crate::{alcatraz_utils, attach, research::Researcher, settings::{AlcatrazTiming, Settings, Thps12Category}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::Watcher};
 
-struct State {
+// all goals & golds: every goal on the goal levels and a gold on every competition
+fn is_tour_complete(state: &alcatraz_utils::TourState, tour: usize) -> bool {
+    let totals = &alcatraz_utils::TOUR_TOTALS[tour];
+    state.goals == totals.goals && state.gold_medals == totals.gold_medals
+}
+
+pub struct State {
     level_name: String,
     goal_count: u32,
     roswell_medal: bool,
     bullring_medal: bool,
+    gold_count: u32,
+    thps1_complete: bool,
+    thps2_complete: bool,
+    total_goal_count: u32,
+    thps1_skaters: u32,
+    thps2_skaters: u32,
+    run_time: f32,
+    is_run_clock_running: bool,
     gamemode: u8,
     is_running: bool,
     is_loading: bool,
@@ -16,117 +30,245 @@ impl State {
     pub fn update(process: &Process, context: &alcatraz_utils::AlcatrazContext, career: &mut alcatraz_utils::CareerState) -> Self {
         career.update(process, context);
 
+        let thps1state = career.get_tour_state(0);
+        let thps2state = career.get_tour_state(1);
+
+        trace!("THPS1 goals: {}, golds: {}, not in the table: {}, THPS2 goals: {}, golds: {}, not in the table: {}", thps1state.goals, thps1state.gold_medals, thps1state.inferred_goals, thps2state.goals, thps2state.gold_medals, thps2state.inferred_goals);
+
         Self {
             level_name: context.get_level_name(process),
             goal_count: career.get_goal_count(),
             roswell_medal: career.get_goal_state(0, 8, 0),
             bullring_medal: career.get_goal_state(1, 7, 0),
+            gold_count: thps1state.gold_medals + thps2state.gold_medals,
+            thps1_complete: is_tour_complete(thps1state, 0),
+            thps2_complete: is_tour_complete(thps2state, 1),
+            total_goal_count: career.get_total_goal_count(),
+            thps1_skaters: career.get_skaters_with_completed_tour(0, |v| is_tour_complete(v, 0)),
+            thps2_skaters: career.get_skaters_with_completed_tour(1, |v| is_tour_complete(v, 1)),
+            run_time: context.get_run_time(),
+            is_run_clock_running: context.is_run_clock_running(),
             gamemode: context.get_gamemode(process),
             is_running: context.is_run_active(process),
             is_loading: context.is_loading(process),
         }
     }
-}
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THPS1+2!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        // the level name is briefly empty while loading, so keep showing the last one
+        if !self.level_name.is_empty() && self.level_name != "None" {
+            variables.set("Level", &self.level_name);
+        }
 
-    let base_addr = process.get_module_address(process_name).unwrap();
+        variables.set("Goals", self.goal_count);
+        variables.set("Medals", self.roswell_medal as u32 + self.bullring_medal as u32);
+        variables.set("Gold Medals", self.gold_count);
+        variables.set("Total Goals", self.total_goal_count);
+        variables.set("THPS1 Skaters", self.thps1_skaters);
+        variables.set("THPS2 Skaters", self.thps2_skaters);
+    }
+}
 
-    let context;
-    loop {
-        asr::print_message("Finding offsets...");
-        let context_result = alcatraz_utils::AlcatrazContext::new(process, base_addr, alcatraz_utils::Game::THPS12);
-        if let Some(ctx) = context_result {
-            asr::print_message("Offsets found!");
-
-            ctx.list_addresses();
-
-            context = ctx;
-            break;
-        } 
-        
-        asr::print_message("Failed to find offsets! Trying again...");
-        asr::future::next_tick().await;
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("level", &self.level_name);
+        w.field("goals", self.goal_count);
+        w.field("roswell", self.roswell_medal);
+        w.field("bullring", self.bullring_medal);
+        w.field("golds", self.gold_count);
+        w.field("thps1_complete", self.thps1_complete);
+        w.field("thps2_complete", self.thps2_complete);
+        w.field("total_goals", self.total_goal_count);
+        w.field("thps1_skaters", self.thps1_skaters);
+        w.field("thps2_skaters", self.thps2_skaters);
+        w.field("run_time", self.run_time);
+        w.field("run_clock_running", self.is_run_clock_running);
+        w.field("mode", self.gamemode);
+        w.field("running", self.is_running);
+        w.field("loading", self.is_loading);
     }
 
-    let mut career = context.get_career_state(process);
-    let mut prev_state = State::update(process, &context, &mut career);
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            level_name: r.field("level")?,
+            goal_count: r.field("goals")?,
+            roswell_medal: r.field("roswell")?,
+            bullring_medal: r.field("bullring")?,
+            gold_count: r.field("golds")?,
+            thps1_complete: r.field("thps1_complete")?,
+            thps2_complete: r.field("thps2_complete")?,
+            total_goal_count: r.field("total_goals")?,
+            thps1_skaters: r.field("thps1_skaters")?,
+            thps2_skaters: r.field("thps2_skaters")?,
+            run_time: r.field("run_time")?,
+            is_run_clock_running: r.field("run_clock_running")?,
+            gamemode: r.field("mode")?,
+            is_running: r.field("running")?,
+            is_loading: r.field("loading")?,
+        })
+    }
+}
 
-    let mut starting_game = false;
+// the level name reads as empty or "None" while loading
+fn level_name(state: &State) -> Option<String> {
+    Some(state.level_name.clone()).filter(|v| !v.is_empty() && v != "None")
+}
 
-    loop {
-        // update vars
-        let mut current_state = State::update(process, &context, &mut career);
+pub struct Splitter {
+    prev_state: State,
+    level_name: Watcher<String>,
+    continuing_series: bool,
+    starting_game: bool,
+    pending_split: bool,
+    run_time: alcatraz_utils::RunTimeAccumulator,
+    // when continuing a series, the timer already holds the earlier games' time and setting game time would throw it away.
+    // game time is left running only while the run clock counts instead, so this game's runs are added on top
+    igt_by_pausing: bool,
+    is_counting: bool,
+}
 
-        // if we see an invalid level name, fill in the previous
-        if current_state.level_name.is_empty() || current_state.level_name == "None" {
-            current_state.level_name = prev_state.level_name.clone();
+impl GameSplitter for Splitter {
+    type State = State;
+
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            // a series run can attach with a level already loaded, which shouldn't look like a level change on the first tick
+            level_name: Watcher::holding_from(level_name(&initial_state).unwrap_or_default()),
+            prev_state: initial_state,
+            continuing_series,
+            starting_game: false,
+            pending_split: false,
+            run_time: alcatraz_utils::RunTimeAccumulator::new(),
+            igt_by_pausing: continuing_series,
+            is_counting: continuing_series, // the series resumed game time for us
         }
+    }
 
-        // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, mut current_state: State) {
+        // the level name reads as empty or "None" while loading, so hold on to the last real one
+        current_state.level_name = self.level_name.update(level_name(&current_state)).current().clone();
+
+        let prev_state = &self.prev_state;
+
+        // pause game time when loading, resume when done.  when timing by the sum of run times, game time follows the run clock instead
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
+        if settings.thps12_timing == AlcatrazTiming::LoadRemoved {
+            if is_loading.changed_to(&true) {
+                timer.pause_game_time();
+                debug!("Starting Load...");
+            } else if is_loading.changed_to(&false) {
+                timer.resume_game_time();
+                debug!("Done Loading");
+            }
         }
 
-        if (current_state.level_name == "Warehouse" || current_state.level_name == "Hangar") && prev_state.level_name == "FrontEnd" {
-            starting_game = true;
-            asr::print_message(format!("Starting a game").as_str());
+        if (current_state.level_name == "Warehouse" || current_state.level_name == "Hangar") && self.level_name.old() == "FrontEnd" {
+            self.starting_game = true;
+            debug!("Starting a game");
         }
 
-        if starting_game && (current_state.level_name != "Warehouse" && current_state.level_name != "Hangar") {
-            starting_game = false;
-            asr::print_message(format!("...or not starting a game").as_str());
+        if self.starting_game && (current_state.level_name != "Warehouse" && current_state.level_name != "Hangar") {
+            self.starting_game = false;
+            debug!("...or not starting a game");
         }
 
-        match asr::timer::state() {
+        match timer.state() {
             TimerState::NotRunning => {
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+                self.igt_by_pausing = false;
+
                 // start when no goals have been completed and starting a first level
-                if starting_game && current_state.goal_count == 0 && current_state.is_running {
-                    if current_state.gamemode == 0x02 {
-                        asr::timer::start();
-                        asr::print_message(format!("Starting timer...").as_str());
+                if self.starting_game && current_state.goal_count == 0 && current_state.is_running {
+                    if settings.thps12_start && current_state.gamemode == 0x02 {
+                        timer.start();
+                        info!("Starting timer...");
+
+                        // game time is set from the run clock, so it shouldn't also run on its own
+                        if settings.thps12_timing == AlcatrazTiming::SumOfRuns {
+                            timer.pause_game_time();
+                            self.run_time.reset();
+                        }
                     }
-                    starting_game = false;
+                    self.starting_game = false;
+                    self.pending_split = false;
                 }
             },
             TimerState::Paused | TimerState::Running => {
                 // split on level changes (except frontend)
-                if !starting_game && !current_state.level_name.is_empty() && current_state.level_name != prev_state.level_name && current_state.level_name != "FrontEnd" {
-                    asr::timer::split();
-                    asr::print_message(format!("Changed level; splitting timer...").as_str());
+                if settings.thps12_split_level && !self.starting_game && !current_state.level_name.is_empty() && self.level_name.changed() && current_state.level_name != "FrontEnd" {
+                    timer.split();
+                    info!("Changed level; splitting timer...");
                 }
 
                 // split when second game is started
-                if ((current_state.roswell_medal && current_state.level_name == "Hangar") || (current_state.bullring_medal && current_state.level_name == "Warehouse")) && starting_game && current_state.is_running {
+                if ((current_state.roswell_medal && current_state.level_name == "Hangar") || (current_state.bullring_medal && current_state.level_name == "Warehouse")) && self.starting_game && current_state.is_running {
                     if current_state.gamemode == 0x02 {
-                        asr::timer::split();
-                        asr::print_message(format!("Changed level; splitting timer...").as_str());
+                        timer.split();
+                        info!("Changed level; splitting timer...");
                     }
-                    starting_game = false;
+                    self.starting_game = false;
                 }
 
                 // split when roswell medal is collected
-                if current_state.roswell_medal && !prev_state.roswell_medal {
-                    asr::timer::split();
-                    asr::print_message(format!("Got Roswell medal; splitting timer...").as_str());
+                if settings.thps12_split_medal && Watcher::between(prev_state.roswell_medal, current_state.roswell_medal).changed_to(&true) {
+                    timer.split();
+                    info!("Got Roswell medal; splitting timer...");
                 }
 
                 // split when bullring medal is collected
-                if current_state.bullring_medal && !prev_state.bullring_medal {
-                    asr::timer::split();
-                    asr::print_message(format!("Got Bullring medal; splitting timer...").as_str());
+                if settings.thps12_split_medal && Watcher::between(prev_state.bullring_medal, current_state.bullring_medal).changed_to(&true) {
+                    timer.split();
+                    info!("Got Bullring medal; splitting timer...");
+                }
+
+                if Watcher::between(prev_state.thps1_complete, current_state.thps1_complete).changed_to(&true) {
+                    debug!("THPS1 goals and golds complete");
+                }
+
+                if Watcher::between(prev_state.thps2_complete, current_state.thps2_complete).changed_to(&true) {
+                    debug!("THPS2 goals and golds complete");
+                }
+
+                // split when all goals and golds in both games are complete, once the run is over
+                let all_complete = Watcher::between(prev_state.thps1_complete && prev_state.thps2_complete, current_state.thps1_complete && current_state.thps2_complete);
+                if settings.thps12_category == Thps12Category::AllGoalsAndGolds && all_complete.changed_to(&true) {
+                    self.pending_split = true;
+                    debug!("All goals and golds complete; ready to split...");
+                }
+
+                if self.pending_split && !current_state.is_running {
+                    timer.split();
+                    info!("Ended run with all goals and golds; splitting timer...");
+
+                    self.pending_split = false;
+                }
+
+                // add up the time of every run
+                if settings.thps12_timing == AlcatrazTiming::SumOfRuns {
+                    if self.igt_by_pausing {
+                        let is_counting = current_state.is_run_clock_running;
+                        if is_counting && !self.is_counting {
+                            timer.resume_game_time();
+                        } else if !is_counting && self.is_counting {
+                            timer.pause_game_time();
+                        }
+                        self.is_counting = is_counting;
+                    } else if let Some(igt) = self.run_time.update(current_state.is_running, current_state.run_time) {
+                        timer.set_game_time(igt);
+                    }
                 }
 
                 // reset when on frontend with 0 pro points
-                if current_state.level_name == "FrontEnd" && current_state.goal_count == 0 {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
+                if settings.thps12_reset && !self.continuing_series && current_state.level_name == "FrontEnd" && current_state.goal_count == 0 {
+                    timer.reset();
+                    info!("Resetting timer...");
+
+                    if settings.thps12_timing == AlcatrazTiming::SumOfRuns {
+                        timer.resume_game_time();
+                    }
+
+                    self.pending_split = false;
                 }
             },
             TimerState::Ended | TimerState::Unknown | _ => {
@@ -134,7 +276,39 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THPS1+2!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let base_addr = attach::module_address(process, process_name).await;
+
+    info!("Finding offsets...");
+    let mut context = attach::retry("offsets", || alcatraz_utils::AlcatrazContext::new(process, base_addr, alcatraz_utils::Game::THPS12)).await;
+
+    info!("Offsets found!");
+    context.list_addresses();
+
+    let mut career = context.get_career_state(process);
+    let mut splitter = Splitter::new(State::update(process, &context, &mut career), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THPS12");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit64);
+
+    loop {
+        settings.refresh();
+        context.refresh(process);
+
+        // update vars
+        let current_state = State::update(process, &context, &mut career);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
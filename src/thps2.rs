@@ -1,5 +1,7 @@
 use asr::{Address, Process, timer::TimerState, time::Duration};
 
+use crate::{attach, memory::MemorySource, research::Researcher, settings::{Settings, Thps2Category}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
+
 // looking for things TODO: implement these alternate offsets based off of the Activision Value release executable:
 // level 0x561c90 / 0x161c90
 // also possibly 0x56a898
@@ -17,239 +19,334 @@ use asr::{Address, Process, timer::TimerState, time::Duration};
 // Loading = 0x55e230
 // GLevel = 0x5674f8 (prevents splits)
 
-struct State {
+#[derive(Copy, Clone)]
+pub struct Offsets {
+    career_profiles: u32,
+    skater: u32,
+    level_table: u32,
+    timer_running: u32,
+    menu_screen: u32,
+    level: u32,
+    mode: u32,
+    vblanks: u32,
+    timer_level: u32,
+}
+
+// TODO: fill in the timestamps and offsets for the other releases (see the notes above for what's been found so far)
+static OFFSETS: Offsets = Offsets {
+    career_profiles: 0x1656cc,
+    skater: 0x1674b8,
+    level_table: 0x139040,
+    timer_running: 0x16B238,
+    menu_screen: 0x15e8d8,
+    level: 0x1674f8,
+    mode: 0x15e8d4,
+    vblanks: 0x16af80,
+    timer_level: 0x15e8f0,
+};
+
+pub struct State {
     is_timer_running: bool,
     timer_end: u32,
     seconds_elapsed: u32,
     level_id: u8,
     mode: u8,
     screen: u8,
-    _gold_count: u32,
+    gold_count: u32,
     medal_count: u32,
     goal_count: u8,
+    no_career_progress: bool,
 }
 
-impl State {
-    pub fn check_for_reset(process: &Process, base_addr: Address) -> bool {
-        for i in 0..15 {
-            let cash = match process.read::<u8>(base_addr + 0x1656cc + (i * 0x104) + 4 as u32) {
-                Ok(v) => v,
-                Err(_) => 0,
-            };
-
-            if cash > 0 {
-                return false;
-            }
+pub struct Watchers {
+    is_timer_running: MemoryWatcher<bool>,
+    screen: MemoryWatcher<u8>,
+    level_id: MemoryWatcher<u8>,
+    mode: MemoryWatcher<u8>,
+    vblanks: MemoryWatcher<u32>,
+    timer_level: MemoryWatcher<u32>,
+}
+
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
+            is_timer_running: MemoryWatcher::new(&[offsets.timer_running as u64]),
+            screen: MemoryWatcher::new(&[offsets.menu_screen as u64]),
+            level_id: MemoryWatcher::new(&[offsets.level as u64]),
+            mode: MemoryWatcher::new(&[offsets.mode as u64]),
+            // a failed read dropping to 0 would look like the run timer went backwards, which commits the run's time
+            vblanks: MemoryWatcher::holding(&[offsets.vblanks as u64]),
+            timer_level: MemoryWatcher::new(&[offsets.timer_level as u64]),
         }
+    }
+}
+
+const PROFILE_SIZE: usize = 0x104;
+const PROFILE_COUNT: usize = 15;
+const LEVEL_SIZE: usize = 0x1ac;
+const LEVEL_COUNT: usize = 14;
+
+impl State {
+    // every career profile is read in one go, since this checks all of them
+    pub fn check_for_reset(process: &impl MemorySource, base_addr: Address, offsets: &Offsets) -> bool {
+        let profiles = match process.read_block(base_addr + offsets.career_profiles, PROFILE_SIZE * PROFILE_COUNT) {
+            Ok(v) => v,
+            Err(_) => return true,
+        };
 
-        return true;
+        (0..PROFILE_COUNT).all(|i| profiles.get::<u8>(i * PROFILE_SIZE + 4).unwrap_or(0) == 0)
     }
 
-    pub fn update(process: &Process, base_addr: Address) -> Self {
+    pub fn update(process: &impl MemorySource, base_addr: Address, offsets: &Offsets, watchers: &mut Watchers) -> Self {
         let mut gold_count = 0;
         let mut medal_count = 0;
         let mut comp_all_cash = 0;
         let mut goal_count = 0;
 
-        // get skater
-        let skater_id = match process.read_pointer_path::<i32>(base_addr, asr::PointerSize::Bit32, &vec!(0x1674b8 as u64, 0x2cc0 as u64)) {
-            Ok(v) => v,
-            Err(_) => -1,
+        // the skater id and the time the run ends sit next to each other, so follow the skater pointer once and read both
+        let skater = process.read::<u32>(base_addr + offsets.skater).and_then(|v| process.read_block(v + 0x2cc0 as u32, 0xc));
+        let (skater_id, time_end) = match &skater {
+            Ok(v) => (v.get::<i32>(0).unwrap_or(-1), v.get::<i32>(8).unwrap_or(0)),
+            Err(_) => (-1, 0),
+        };
+
+        // the comp flag for every level, including the one the timer uses
+        let is_comp: Vec<bool> = match process.read_block(base_addr + offsets.level_table, LEVEL_SIZE * LEVEL_COUNT) {
+            Ok(levels) => (0..LEVEL_COUNT).map(|i| levels.get::<bool>(i * LEVEL_SIZE).unwrap_or(false)).collect(),
+            Err(_) => vec![false; LEVEL_COUNT],
         };
 
         if skater_id > 0 && skater_id < 15 {    // TODO: figure out secret skaters
-            let skater_profile = 0x1656cc + (skater_id as u32 * 0x104);
-            let medal_offset = skater_profile + 0xc;
+            let skater_profile = offsets.career_profiles + (skater_id as u32 * PROFILE_SIZE as u32);
 
-            for i in 0..13 {
-                let is_comp = match process.read::<bool>(base_addr + 0x139040 + (i * 0x1ac) as u32) {
-                    Ok(v) => v,
-                    Err(_) => false,
-                };
+            if let Ok(profile) = process.read_block(base_addr + skater_profile, PROFILE_SIZE) {
+                for i in 0..13 {
+                    if is_comp[i] {
+                        let medal_flags = profile.get::<u16>(0xc + i * 2).unwrap_or(0);
 
-                if is_comp {
-                    let medal_flags = match process.read::<u16>(base_addr + medal_offset + (i * 2) as u32) {
-                        Ok(v) => v,
-                        Err(_) => 0,
-                    };
+                        if (medal_flags & 0x1c00).count_ones() > 0 {
+                            medal_count += 1;
+                        }
 
-                    if (medal_flags & 0x1c00).count_ones() > 0 {
-                        medal_count += 1;
-                    }
+                        if medal_flags & 0x400 > 1 {
+                            gold_count += 1;
+                        }
 
-                    if medal_flags & 0x400 > 1 {
-                        gold_count += 1;
-                    }
-
-                    if medal_flags & 0x8000 > 1 {
-                        comp_all_cash += 1;
+                        if medal_flags & 0x8000 > 1 {
+                            comp_all_cash += 1;
+                        }
                     }
                 }
-            }
 
-            goal_count = match process.read::<u8>(base_addr + skater_profile as u32) {
-                Ok(v) => v + comp_all_cash,
-                Err(_) => 0,
-            };
+                goal_count = profile.get::<u8>(0).unwrap_or(0) + comp_all_cash;
+            }
         }
 
-        let is_timer_running = match process.read::<bool>(base_addr + 0x16B238 as u32) {
-            Ok(v) => v,
-            Err(_) => false,
-        };
+        let is_timer_running = *watchers.is_timer_running.update(process, base_addr).current();
+        let screen = *watchers.screen.update(process, base_addr).current();
+        let vblanks = *watchers.vblanks.update(process, base_addr).current();
 
         State {
             is_timer_running,
+            level_id: *watchers.level_id.update(process, base_addr).current(),
+            mode: *watchers.mode.update(process, base_addr).current(),
+            screen,
 
-            level_id: match process.read::<u8>(base_addr + 0x1674f8 as u32) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+            // used for igt only, so clamp it to max run time
+            seconds_elapsed: if is_timer_running {
+                let time_left = ((time_end - vblanks as i32) / 60).max(0) as u32;
+                let level_id = (*watchers.timer_level.update(process, base_addr).current()).clamp(0, 13);
 
-            mode: match process.read::<u8>(base_addr + 0x15e8d4 as u32) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+                let max_time = if is_comp[level_id as usize] {
+                    1 * 60 // 1 minutes * 60 seconds
+                } else {
+                    2 * 60 // 2 minutes * 60 seconds
+                };
 
-            screen: match process.read::<u8>(base_addr + 0x15e8d8 as u32) {
-                Ok(v) => v,
-                Err(_) => 0,
+                max_time - time_left
+            } else {
+                0
             },
 
-            // used for igt only, so clamp it to max run time
-            seconds_elapsed: match process.read::<u32>(base_addr + 0x16af80 as u32) {
-                Ok(vblanks) => {
-                    if is_timer_running {
-                        let time_end = match process.read_pointer_path::<i32>(base_addr, asr::PointerSize::Bit32, &vec!(0x1674b8 as u64, 0x2cc8 as u64)) {
-                            Ok(v) => v,
-                            Err(_) => 0,
-                        };
-
-                        let time_left = ((time_end as i32 - vblanks as i32) / 60).max(0) as u32;
-
-                        let level_id = match process.read::<u32>(base_addr + 0x15e8f0 as u32) {
-                            Ok(v) => v.clamp(0, 13),
-                            Err(_) => 0,
-                        };
-
-                        let is_comp = match process.read::<bool>(base_addr + 0x139040 + (level_id * 0x1ac) as u32) {
-                            Ok(v) => v,
-                            Err(_) => false,
-                        };
-        
-                        let max_time = if is_comp {
-                            1 * 60 // 1 minutes * 60 seconds
-                        } else {
-                            2 * 60 // 2 minutes * 60 seconds
-                        };
-
-                        max_time - time_left
-                    } else {
-                        0
-                    }
-                },
-                Err(_) => 0,
-            },
-
-            timer_end: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x1674b8 as u64, 0x2cc8 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+            timer_end: time_end as u32,
 
-            _gold_count: gold_count,
+            gold_count: gold_count,
             medal_count: medal_count,
             goal_count: goal_count,
+
+            // only needed for resets, which only happen on menus
+            no_career_progress: screen != 6 && Self::check_for_reset(process, base_addr, offsets),
         }
     }
+
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Goals", self.goal_count);
+        variables.set("Medals", self.medal_count);
+        variables.set("Gold Medals", self.gold_count);
+    }
 }
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THPS2!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("timer_running", self.is_timer_running);
+        w.field("timer_end", self.timer_end);
+        w.field("seconds", self.seconds_elapsed);
+        w.field("level", self.level_id);
+        w.field("mode", self.mode);
+        w.field("screen", self.screen);
+        w.field("golds", self.gold_count);
+        w.field("medals", self.medal_count);
+        w.field("goals", self.goal_count);
+        w.field("no_progress", self.no_career_progress);
+    }
 
-    let base_addr = process.get_module_address(process_name).unwrap();
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            is_timer_running: r.field("timer_running")?,
+            timer_end: r.field("timer_end")?,
+            seconds_elapsed: r.field("seconds")?,
+            level_id: r.field("level")?,
+            mode: r.field("mode")?,
+            screen: r.field("screen")?,
+            gold_count: r.field("golds")?,
+            medal_count: r.field("medals")?,
+            goal_count: r.field("goals")?,
+            no_career_progress: r.field("no_progress")?,
+        })
+    }
+}
 
-    let mut prev_state = State::update(process, base_addr);
-    let mut game_done = false;
-    let mut level_changed = false;
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+    game_done: bool,
+    level_changed: bool,
+    igt_accumulator: i64,   // igt in seconds
+    prev_igt: Duration,
+    // when continuing a series, the timer already holds the earlier games' time and setting game time would throw it away.
+    // game time is left running only while the run clock counts instead, so this game's runs are added on top
+    igt_by_pausing: bool,
+    is_counting: bool,
+}
 
-    let mut igt_accumulator: i64 = 0;   // igt in seconds
-    let mut prev_igt = Duration::seconds(-1);
+impl GameSplitter for Splitter {
+    type State = State;
+
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+            game_done: false,
+            level_changed: false,
+            igt_accumulator: 0,
+            prev_igt: Duration::seconds(-1),
+            igt_by_pausing: continuing_series,
+            is_counting: continuing_series, // the series resumed game time for us
+        }
+    }
 
-    loop {
-        // update vars
-        let mut current_state = State::update(process, base_addr);
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, mut current_state: State) {
+        let prev_state = &self.prev_state;
 
         // if the end of time is changing, don't count any seconds yet, we're in a comp intro (also if the end is in 1 minute we know that's not right and we're starting the comp intro)
-        let is_timer_unstable = current_state.timer_end != prev_state.timer_end || current_state.timer_end == 3630;    
+        let is_timer_unstable = Watcher::between(prev_state.timer_end, current_state.timer_end).changed() || current_state.timer_end == 3630;
 
         if is_timer_unstable {
             current_state.seconds_elapsed = prev_state.seconds_elapsed;
         }
 
-        match asr::timer::state() {
+        match timer.state() {
             TimerState::NotRunning => {
-                if game_done {
-                    game_done = false;
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+                self.igt_by_pausing = false;
+
+                if self.game_done {
+                    self.game_done = false;
                 }
 
-                if current_state.mode == 1 && current_state.goal_count == 0 && current_state.level_id == 0 && current_state.is_timer_running {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer...").as_str());
+                if settings.thps2_start && current_state.mode == 1 && current_state.goal_count == 0 && current_state.level_id == 0 && current_state.is_timer_running {
+                    timer.start();
+                    info!("Starting timer...");
 
-                    asr::timer::pause_game_time();
-                    igt_accumulator = 0;
-                    level_changed = false;
+                    timer.pause_game_time();
+                    self.igt_accumulator = 0;
+                    self.level_changed = false;
                 }
             },
             TimerState::Paused | TimerState::Running => {
                 // split on level changes (except going to menu)
-                if current_state.level_id != prev_state.level_id {
-                    level_changed = true;
+                if Watcher::between(prev_state.level_id, current_state.level_id).changed() {
+                    self.level_changed = true;
                 }
 
-                if current_state.screen == 6 && level_changed {
-                    level_changed = false;
-                    asr::timer::split();
-                    asr::print_message(format!("Changed levels; splitting timer...").as_str());
+                if current_state.screen == 6 && self.level_changed {
+                    self.level_changed = false;
+
+                    if settings.thps2_split_level {
+                        timer.split();
+                        info!("Changed levels; splitting timer...");
+                    }
                 }
 
-                // split when all medals collected 
-                // TODO: add setting to only split when all goals and goals are collected
-                if !game_done && current_state.medal_count == 3 {
-                    game_done = true;
-                    asr::timer::split();
-                    asr::print_message(format!("Collected all medals; splitting timer...").as_str());
+                // split when all medals (or golds) are collected
+                match settings.thps2_category {
+                    Thps2Category::AllMedals => {
+                        if !self.game_done && current_state.medal_count == 3 {
+                            self.game_done = true;
+                            timer.split();
+                            info!("Collected all medals; splitting timer...");
+                        }
+                    },
+                    Thps2Category::AllGolds => {
+                        if !self.game_done && current_state.gold_count == 3 {
+                            self.game_done = true;
+                            timer.split();
+                            info!("Collected all golds; splitting timer...");
+                        }
+                    },
                 }
 
                 // reset when on a menu and no goals are complete on any skater
-                if current_state.screen != 6 && State::check_for_reset(process, base_addr) {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
+                if settings.thps2_reset && !self.continuing_series && current_state.screen != 6 && current_state.no_career_progress {
+                    timer.reset();
+                    info!("Resetting timer...");
 
-                    prev_igt = Duration::seconds(-1);
-                    asr::timer::resume_game_time();
+                    self.prev_igt = Duration::seconds(-1);
+                    timer.resume_game_time();
 
-                    level_changed = false;
-                    igt_accumulator = 0;    // so igt doesn't stick around
+                    self.level_changed = false;
+                    self.igt_accumulator = 0;    // so igt doesn't stick around
                 }
 
                 // calculate igt
-                // commit run's time when either the timer has stopped (run ended) or current time is lower than previous while timer is running
-                if (!current_state.is_timer_running && prev_state.is_timer_running) || (current_state.seconds_elapsed < prev_state.seconds_elapsed && prev_state.is_timer_running) {
-                    igt_accumulator += prev_state.seconds_elapsed as i64;
-                }
-
-                let igt_duration = if current_state.is_timer_running {
-                    Duration::seconds(igt_accumulator + (current_state.seconds_elapsed as i64))
+                if self.igt_by_pausing {
+                    let is_counting = current_state.is_timer_running && !is_timer_unstable;
+                    if is_counting && !self.is_counting {
+                        timer.resume_game_time();
+                    } else if !is_counting && self.is_counting {
+                        timer.pause_game_time();
+                    }
+                    self.is_counting = is_counting;
                 } else {
-                    Duration::seconds(igt_accumulator)
-                };
+                    // commit run's time when either the timer has stopped (run ended) or current time is lower than previous while timer is running
+                    let is_timer_running = Watcher::between(prev_state.is_timer_running, current_state.is_timer_running);
+                    if is_timer_running.changed_to(&false) || (current_state.seconds_elapsed < prev_state.seconds_elapsed && prev_state.is_timer_running) {
+                        self.igt_accumulator += prev_state.seconds_elapsed as i64;
+                    }
 
-                // prevent excess messaging and only send igt when relevant
-                if igt_duration != prev_igt {
-                    prev_igt = igt_duration;
-                    asr::timer::set_game_time(igt_duration);
+                    let igt_duration = if current_state.is_timer_running {
+                        Duration::seconds(self.igt_accumulator + (current_state.seconds_elapsed as i64))
+                    } else {
+                        Duration::seconds(self.igt_accumulator)
+                    };
+
+                    // prevent excess messaging and only send igt when relevant
+                    if igt_duration != self.prev_igt {
+                        self.prev_igt = igt_duration;
+                        timer.set_game_time(igt_duration);
+                    }
                 }
             },
             TimerState::Ended | TimerState::Unknown | _ => {
@@ -257,8 +354,136 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THPS2!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let base_addr = attach::module_address(process, process_name).await;
+
+    let offsets = OFFSETS;
+
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &offsets, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THPS2");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
+
+    loop {
+        settings.refresh();
+
+        // update vars
+        let current_state = State::update(process, base_addr, &offsets, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
+
+    const BASE: u32 = 0x400000;
+    const SKATER: u32 = 0x20000000;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        offsets: Offsets,
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+    }
+
+    impl Game {
+        // starts on a fresh save, with a 2 minute run timer that hasn't started counting down
+        fn new() -> Self {
+            let offsets = OFFSETS;
+            let mut memory = MockMemory::new();
+            memory.write_bytes(BASE + offsets.career_profiles, &[0; PROFILE_SIZE * PROFILE_COUNT]);
+            memory.write_bytes(BASE + offsets.level_table, &[0; LEVEL_SIZE * LEVEL_COUNT]);
+            memory.write(BASE + offsets.skater, SKATER);
+            memory.write(SKATER + 0x2cc0, [0i32, 0, 2 * 60 * 60]);
+
+            let mut watchers = Watchers::new(&offsets);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE as u64), &offsets, &mut watchers), false);
+
+            Self { offsets, memory, watchers, splitter, timer: ReplayTimer::new(None) }
+        }
+
+        fn set(&mut self, offset: u32, value: u8) {
+            self.memory.write(BASE + offset, value);
+        }
+
+        fn set_vblanks(&mut self, vblanks: u32) {
+            self.memory.write(BASE + self.offsets.vblanks, vblanks);
+        }
+
+        // starts a new career and the timer with it
+        fn start(&mut self) -> Vec<TimerEvent> {
+            self.set(self.offsets.mode, 1);
+            self.set(self.offsets.menu_screen, 6);
+            self.set(self.offsets.timer_running, 1);
+            self.tick()
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = State::update(&self.memory, Address::new(BASE as u64), &self.offsets, &mut self.watchers);
+            self.splitter.update(&mut self.timer, &Settings::default(), state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_on_a_new_career_and_splits_on_level_changes() {
+        let mut game = Game::new();
+        assert_eq!(game.start(), [Start, PauseGameTime]);
+
+        game.set(game.offsets.level, 1);
+        assert_eq!(game.tick(), [Split, SetGameTime(Duration::seconds(0))]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn game_time_adds_up_every_run() {
+        let mut game = Game::new();
+        assert_eq!(game.start(), [Start, PauseGameTime]);
+
+        game.set_vblanks(10 * 60);
+        assert_eq!(game.tick(), [SetGameTime(Duration::seconds(10))]);
+
+        // the run ends, then the next one starts from the top of the clock
+        game.set(game.offsets.timer_running, 0);
+        assert!(game.tick().is_empty());
+
+        game.set(game.offsets.timer_running, 1);
+        game.set_vblanks(0);
+        assert!(game.tick().is_empty());
+
+        game.set_vblanks(5 * 60);
+        assert_eq!(game.tick(), [SetGameTime(Duration::seconds(15))]);
+    }
+
+    #[test]
+    fn resets_on_a_menu_once_no_skater_has_progress() {
+        let mut game = Game::new();
+        game.memory.write(BASE + game.offsets.career_profiles + 4, 1u8);
+        game.timer.start();
+        assert_eq!(game.tick(), [SetGameTime(Duration::seconds(0))]);
+
+        game.memory.write(BASE + game.offsets.career_profiles + 4, 0u8);
+        assert_eq!(game.tick(), [Reset, ResumeGameTime, SetGameTime(Duration::seconds(0))]);
+    }
+}
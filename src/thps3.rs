@@ -1,10 +1,45 @@
 use asr::{Address, Process, timer::TimerState};
 
+use crate::{attach, memory::{Block, MemorySource}, research::Researcher, scan::{self, RootSignature}, settings::{Settings, Thps3Category}, split_rules::{Primed, SplitRule, SplitRules}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
+
 // NOTES:
 // POINTERS TO LEVEL IN INTEGER: 0x4e1e90 -> 0x134 -> 0x14 -> 0x690
 // POINTERS TO GOAL FLAGS: 0x4e1e90 -> 0x134 -> 0x14 -> 0x564 (EACH SET OF FLAGS IS 8 BYTES, 9 GOALS OR 3 MEDALS.  FOR MEDALS, BRONZE IS LSB)
 
-struct State {
+#[derive(Copy, Clone)]
+pub struct Offsets {
+    career: u32,
+    loading: u32,
+    timer_running: u32,
+    paused: u32,
+}
+
+static OFFSETS: Offsets = Offsets {
+    career: 0x4e1e90,
+    loading: 0x1D0620,
+    timer_running: 0x450BC0,
+    paused: 0x450BC8,
+};
+
+// code that references each root pointer.  none have been mapped out for THPS3 yet, so these are the places to add them
+static CAREER_SIGNATURES: &[RootSignature] = &[];
+static LOADING_SIGNATURES: &[RootSignature] = &[];
+static TIMER_RUNNING_SIGNATURES: &[RootSignature] = &[];
+static PAUSED_SIGNATURES: &[RootSignature] = &[];
+
+impl Offsets {
+    // each pointer from a signature scan, or the stock exe's offset if none match
+    pub fn get(process: &Process, base_addr: Address, module_size: u64) -> Self {
+        Self {
+            career: scan::resolve_or(process, base_addr, module_size, "the career pointer", CAREER_SIGNATURES, OFFSETS.career),
+            loading: scan::resolve_or(process, base_addr, module_size, "the loading flag", LOADING_SIGNATURES, OFFSETS.loading),
+            timer_running: scan::resolve_or(process, base_addr, module_size, "the run timer flag", TIMER_RUNNING_SIGNATURES, OFFSETS.timer_running),
+            paused: scan::resolve_or(process, base_addr, module_size, "the pause flag", PAUSED_SIGNATURES, OFFSETS.paused),
+        }
+    }
+}
+
+pub struct State {
     goal_count: u32,
     medal_count: u32,
     gold_count: u32,
@@ -17,6 +52,28 @@ struct State {
     comp_is_over: bool,
 }
 
+pub struct Watchers {
+    career: MemoryWatcher<u32>,
+    is_loading: MemoryWatcher<bool>,
+    is_timer_running: MemoryWatcher<bool>,
+    is_paused: MemoryWatcher<bool>,
+    comp_ranking: MemoryWatcher<u32>,
+    comp_is_over: MemoryWatcher<bool>,
+}
+
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
+            career: MemoryWatcher::new(&[offsets.career as u64, 0x134, 0x14]),
+            is_loading: MemoryWatcher::new(&[offsets.loading as u64]),
+            is_timer_running: MemoryWatcher::new(&[offsets.timer_running as u64]),
+            is_paused: MemoryWatcher::new(&[offsets.paused as u64]),
+            comp_ranking: MemoryWatcher::new(&[offsets.career as u64, 0x45c, 0x160]),
+            comp_is_over: MemoryWatcher::new(&[offsets.career as u64, 0x45c, 0x15c]),
+        }
+    }
+}
+
 const LEVEL_COUNT: u32 = 9;
 const LEVEL_IS_COMP: [bool; LEVEL_COUNT as usize] = [
     false,
@@ -31,179 +88,208 @@ const LEVEL_IS_COMP: [bool; LEVEL_COUNT as usize] = [
 ];
 
 impl State {
-    fn get_goal_count(process: &Process, base_addr: Address) -> u32 {
+    // goal flags for every level, 8 bytes each, followed further on by the level id.  read in one go
+    const CAREER_BLOCK_START: u32 = 0x564;
+    const CAREER_BLOCK_SIZE: usize = 0x694 - 0x564;
+
+    fn get_goal_count(career: &Block) -> u32 {
         let mut result = 0;
 
         for i in 0..LEVEL_COUNT {
             if !LEVEL_IS_COMP[i as usize] {
-                match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x4e1e90 as u64, 0x134 as u64, 0x14 as u64, (0x564 + (i * 8)) as u64)) {
-                    Ok(v) => result += v.count_ones(),
-                    Err(_) => {}    // do nothing, we either lost the process or don't have a career initialized
-                }
+                result += career.get::<u32>((i * 8) as usize).unwrap_or(0).count_ones();
             }
         }
 
         return result;
     }
 
-    fn get_medal_count(process: &Process, base_addr: Address) -> (u32, u32) {
+    fn get_medal_count(career: &Block) -> (u32, u32) {
         let mut num_medals = 0;
         let mut num_gold = 0;
 
         for i in 0..LEVEL_COUNT {
             if LEVEL_IS_COMP[i as usize] {
-                match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x4e1e90 as u64, 0x134 as u64, 0x14 as u64, (0x564 + (i * 8)) as u64)) {
-                    Ok(v) => {
-                        if v != 0 {
-                            num_medals += 1;
-                        }
-
-                        num_gold += match v {
-                            0x04 => 1,
-                            _ => 0,
-                        };
-                    }
-                    Err(_) => {}    // do nothing, we either lost the process or don't have a career initialized
+                let v = career.get::<u32>((i * 8) as usize).unwrap_or(0);
+                if v != 0 {
+                    num_medals += 1;
                 }
+
+                num_gold += match v {
+                    0x04 => 1,
+                    _ => 0,
+                };
             }
         }
 
         return (num_medals, num_gold);
     }
 
-    pub fn update(process: &Process, base_addr: Address) -> Self {
-        let (medal_count, gold_count) = Self::get_medal_count(process, base_addr);
+    pub fn update(process: &impl MemorySource, base_addr: Address, watchers: &mut Watchers) -> Self {
+        // an empty block if we either lost the process or don't have a career initialized, which reads as all zeroes
+        let career = match *watchers.career.update(process, base_addr).current() {
+            0 => Block::default(),
+            v => process.read_block(v + Self::CAREER_BLOCK_START, Self::CAREER_BLOCK_SIZE).unwrap_or_default(),
+        };
+
+        let (medal_count, gold_count) = Self::get_medal_count(&career);
 
         State {
-            goal_count: Self::get_goal_count(process, base_addr),
+            goal_count: Self::get_goal_count(&career),
             medal_count, 
             gold_count,
 
-            level_id: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x4e1e90 as u64, 0x134 as u64, 0x14 as u64, 0x690 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            is_loading: match process.read::<bool>(base_addr + 0x1D0620 as u32) {
-                Ok(v) => v,
-                Err(_) => false,
-            },
+            level_id: career.get::<u32>((0x690 - Self::CAREER_BLOCK_START) as usize).unwrap_or(0),
 
-            is_timer_running: match process.read::<bool>(base_addr + 0x450BC0 as u32) {
-                Ok(v) => v,
-                Err(_) => false,
-            },
+            is_loading: *watchers.is_loading.update(process, base_addr).current(),
+            is_timer_running: *watchers.is_timer_running.update(process, base_addr).current(),
+            is_paused: *watchers.is_paused.update(process, base_addr).current(),
+            comp_ranking: *watchers.comp_ranking.update(process, base_addr).current(),
+            comp_is_over: *watchers.comp_is_over.update(process, base_addr).current(),
+        }
+    }
 
-            is_paused: match process.read::<bool>(base_addr + 0x450BC8 as u32) {
-                Ok(v) => v,
-                Err(_) => false,
-            },
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Goals", self.goal_count);
+        variables.set("Medals", self.medal_count);
+        variables.set("Gold Medals", self.gold_count);
+    }
+}
 
-            comp_ranking: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x4e1e90 as u64, 0x45c as u64, 0x160 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("goals", self.goal_count);
+        w.field("medals", self.medal_count);
+        w.field("golds", self.gold_count);
+        w.field("level", self.level_id);
+        w.field("loading", self.is_loading);
+        w.field("timer_running", self.is_timer_running);
+        w.field("paused", self.is_paused);
+        w.field("comp_ranking", self.comp_ranking);
+        w.field("comp_over", self.comp_is_over);
+    }
 
-            comp_is_over: match process.read_pointer_path::<bool>(base_addr, asr::PointerSize::Bit32, &vec!(0x4e1e90 as u64, 0x45c as u64, 0x15c as u64)) {
-                Ok(v) => v,
-                Err(_) => false,
-            },
-        }
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            goal_count: r.field("goals")?,
+            medal_count: r.field("medals")?,
+            gold_count: r.field("golds")?,
+            level_id: r.field("level")?,
+            is_loading: r.field("loading")?,
+            is_timer_running: r.field("timer_running")?,
+            is_paused: r.field("paused")?,
+            comp_ranking: r.field("comp_ranking")?,
+            comp_is_over: r.field("comp_over")?,
+        })
     }
 }
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THPS3!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
-
-    let base_addr = process.get_module_address(process_name).unwrap();
+static RULES: &[SplitRule<State>] = &[
+    // any% end (end of medal run on tokyo)
+    // NOTE: the check is if we're on tokyo, the competition class is initialized, the competition is over, we're in at least 3rd place. 
+    // because this has been inconsistent in the past, the medal check is a backup if that fails.  the medal count is only updated after the ceremony, so it's going to be late for splitting
+    SplitRule {
+        name: "Finished Tokyo",
+        enabled: |s| s.thps3_category != Thps3Category::AllGoalsAndGolds,
+        condition: |_, s| s.medal_count == 3,
+        rearm: Some(|_, s| s.level_id != 8 && s.medal_count < 3),
+        // comp round and placing may be incorrect when starting tokyo, so only trust them once we've seen the comp start
+        primed: Some(Primed {
+            start: |_, s| s.level_id == 8 && !s.comp_is_over && s.medal_count < 3,
+            stop: |_, s| s.level_id != 8,
+            condition: |_, s| s.level_id == 8 && s.comp_is_over && s.comp_ranking <= 3,
+        }),
+    },
+];
 
-    let mut foundry_started = false;
-    let mut tokyo_started = false;
-    let mut tokyo_complete = false;
-    let mut all_goals_and_golds_complete = false;
-    let mut prev_state = State::update(process, base_addr);
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+    foundry_started: bool,
+    rules: SplitRules<State>,
+    all_goals_and_golds_complete: bool,
+}
 
-    loop {
-        // update vars
-        let current_state = State::update(process, base_addr);
+impl GameSplitter for Splitter {
+    type State = State;
 
-        // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+            foundry_started: false,
+            rules: SplitRules::new(RULES),
+            all_goals_and_golds_complete: false,
         }
+    }
 
-        if foundry_started && current_state.level_id != 1 {
-            foundry_started = false;
-        }
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, current_state: State) {
+        let prev_state = &self.prev_state;
 
-        // comp round and placing may be incorrect when starting tokyo, so store when we see the comp start. essentially this tracks initialization
-        if !tokyo_started && current_state.level_id == 8 && !current_state.comp_is_over && current_state.medal_count < 3 {
-            asr::print_message(format!("Tokyo started!").as_str());
-            tokyo_started = true;
-        }
+        let level_id = Watcher::between(prev_state.level_id, current_state.level_id);
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
 
-        if tokyo_started && current_state.level_id != 8 {
-            asr::print_message(format!("Tokyo un-started!").as_str());
-            tokyo_started = false;
+        // pause game time when loading, resume when done
+        if is_loading.changed_to(&true) {
+            timer.pause_game_time();
+            debug!("Starting Load...");
+        } else if is_loading.changed_to(&false) {
+            timer.resume_game_time();
+            debug!("Done Loading");
         }
 
-        if tokyo_complete && current_state.level_id != 8 && current_state.medal_count < 3 {
-            tokyo_complete = false;
+        if self.foundry_started && current_state.level_id != 1 {
+            self.foundry_started = false;
         }
 
-        if (current_state.goal_count != prev_state.goal_count || current_state.gold_count != prev_state.gold_count) && current_state.goal_count == 54 && current_state.gold_count == 3 {
-            all_goals_and_golds_complete = true;
+        let goal_count = Watcher::between(prev_state.goal_count, current_state.goal_count);
+        let gold_count = Watcher::between(prev_state.gold_count, current_state.gold_count);
+
+        if (goal_count.changed() || gold_count.changed()) && current_state.goal_count == 54 && current_state.gold_count == 3 {
+            self.all_goals_and_golds_complete = true;
         }
 
-        if all_goals_and_golds_complete && (current_state.goal_count != 54 || current_state.gold_count != 3) {
-            all_goals_and_golds_complete = false;
+        if self.all_goals_and_golds_complete && (current_state.goal_count != 54 || current_state.gold_count != 3) {
+            self.all_goals_and_golds_complete = false;
         }
 
-        match asr::timer::state() {
+        match timer.state() {
             TimerState::NotRunning => {
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+
                 // can't split on level change, so store that it had changed
-                if current_state.level_id == 1 && prev_state.level_id == 0 && current_state.goal_count == 0 {
-                    foundry_started = true;
+                if *level_id.old() == 0 && level_id.changed_to(&1) && current_state.goal_count == 0 {
+                    self.foundry_started = true;
                 }
 
                 // when goal cams end, start timer
-                if foundry_started && !current_state.is_loading && !current_state.is_paused && current_state.is_timer_running {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer...").as_str());
+                if settings.thps3_start && self.foundry_started && !current_state.is_loading && !current_state.is_paused && current_state.is_timer_running {
+                    timer.start();
+                    info!("Starting timer...");
                 }
             },
             TimerState::Paused | TimerState::Running => {
                 // split on level changes (except skateshop and tokyo to cruise ship, since that's handled when the comp ends)
-                if current_state.level_id != prev_state.level_id && current_state.level_id != 0 && prev_state.level_id != 8 && current_state.level_id != 9 {
-                    asr::timer::split();
-                    asr::print_message(format!("Changed level; splitting timer...").as_str());
+                if settings.thps3_split_level && level_id.changed() && current_state.level_id != 0 && prev_state.level_id != 8 && current_state.level_id != 9 {
+                    timer.split();
+                    info!("Changed level; splitting timer...");
                 }
 
-                // any% end (end of medal run on tokyo)
-                // NOTE: the check is if we're on tokyo, the competition class is initialized, the competition is over, we're in at least 3rd place. 
-                // because this has been inconsistent in the past, the medal check is a backup if that fails.  the medal count is only updated after the ceremony, so it's going to be late for splitting
-                if !tokyo_complete && ((current_state.level_id == 8 && tokyo_started && current_state.comp_is_over && current_state.comp_ranking <= 3) || current_state.medal_count == 3) {
-                    tokyo_complete = true;
-                    asr::timer::split();
-                    asr::print_message(format!("Finished Tokyo; splitting timer...").as_str());
-                }
+                self.rules.update(timer, settings, prev_state, &current_state);
 
                 // ag&g end (all goals and golds collected and run is ended)
-                if all_goals_and_golds_complete && !current_state.is_timer_running {
-                    all_goals_and_golds_complete = false;
-                    asr::timer::split();
-                    asr::print_message(format!("Collected all goals and golds; splitting timer...").as_str());
+                if settings.thps3_category != Thps3Category::AnyPercent && self.all_goals_and_golds_complete && !current_state.is_timer_running {
+                    self.all_goals_and_golds_complete = false;
+                    timer.split();
+                    info!("Collected all goals and golds; splitting timer...");
                 }
 
                 // reset when going back to skateshop
-                if current_state.level_id == 0 && current_state.goal_count == 0 {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
+                if settings.thps3_reset && !self.continuing_series && current_state.level_id == 0 && current_state.goal_count == 0 {
+                    timer.reset();
+                    info!("Resetting timer...");
                 }
             },
             TimerState::Ended | TimerState::Unknown | _ => {
@@ -211,8 +297,127 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THPS3!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let (base_addr, module_size) = attach::module_range(process, process_name).await;
+    let offsets = Offsets::get(process, base_addr, module_size);
+
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THPS3");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
+
+    loop {
+        settings.refresh();
+
+        // update vars
+        let current_state = State::update(process, base_addr, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use asr::PointerSize;
+    use bytemuck::NoUninit;
+
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
+
+    const BASE: u64 = 0x400000;
+    const CAREER: u32 = 0x20000000;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        offsets: Offsets,
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+    }
+
+    impl Game {
+        // starts with a career that has nothing done yet
+        fn new() -> Self {
+            let offsets = OFFSETS;
+            let mut memory = MockMemory::new();
+            memory.write_pointer_path(Address::new(BASE), PointerSize::Bit32, &[offsets.career as u64, 0x134, 0x14], CAREER);
+            memory.write_bytes(CAREER + State::CAREER_BLOCK_START, &[0; State::CAREER_BLOCK_SIZE]);
+
+            let mut watchers = Watchers::new(&offsets);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE), &mut watchers), false);
+
+            Self { offsets, memory, watchers, splitter, timer: ReplayTimer::new(None) }
+        }
+
+        fn set<T: NoUninit>(&mut self, path: &[u64], value: T) {
+            self.memory.write_pointer_path(Address::new(BASE), PointerSize::Bit32, path, value);
+        }
+
+        fn set_level(&mut self, level_id: u32) {
+            self.memory.write(CAREER + 0x690, level_id);
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = State::update(&self.memory, Address::new(BASE), &mut self.watchers);
+            self.splitter.update(&mut self.timer, &Settings::default(), state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_once_foundry_gameplay_begins() {
+        let mut game = Game::new();
+        game.set_level(1);
+        assert!(game.tick().is_empty());
+
+        game.set(&[game.offsets.timer_running as u64], 1u8);
+        assert_eq!(game.tick(), [Start]);
+
+        game.set_level(2);
+        assert_eq!(game.tick(), [Split]);
+    }
+
+    #[test]
+    fn tokyo_splits_when_the_comp_ends_in_the_top_three() {
+        let mut game = Game::new();
+        game.timer.start();
+        game.set_level(8);
+        assert_eq!(game.tick(), [Split]);
+
+        game.set(&[game.offsets.career as u64, 0x45c, 0x160], 2u32);
+        game.set(&[game.offsets.career as u64, 0x45c, 0x15c], 1u8);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn resets_in_the_skateshop_without_goals() {
+        let mut game = Game::new();
+        game.set_level(1);
+        game.memory.write(CAREER + State::CAREER_BLOCK_START, 0x1u32);
+        game.timer.start();
+        assert_eq!(game.tick(), [Split]);
+
+        game.set_level(0);
+        assert!(game.tick().is_empty());
+
+        game.memory.write(CAREER + State::CAREER_BLOCK_START, 0u32);
+        assert_eq!(game.tick(), [Reset]);
+    }
+}
@@ -1,14 +1,71 @@
 use asr::{timer::TimerState, Process};
 
-use crate::alcatraz_utils;
+use crate::{alcatraz_utils, attach, research::Researcher, settings::{AlcatrazTiming, Settings}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::Watcher};
 
-struct State {
+// every goal level in a game has the same goals, and every competition the same medals, so the first of each stands in for all of them
+fn first_level_with(tour: usize, has: impl Fn(&alcatraz_utils::GoalTotals) -> bool) -> alcatraz_utils::GoalTotals {
+    alcatraz_utils::LEVEL_TOTALS[tour].iter().copied().find(|v| has(v)).unwrap_or_default()
+}
+
+// 1 star for a goal on every level, 2 for all goals and golds, 3 for all pro goals and platinums on top
+fn count_stars(state: &alcatraz_utils::TourState, tour: usize) -> u8 {
+    let totals = &alcatraz_utils::TOUR_TOTALS[tour];
+
+    if state.goals == totals.goals && state.gold_medals == totals.gold_medals {
+        if state.pro_goals == totals.pro_goals {
+            3
+        } else {
+            2
+        }
+    } else if state.levels_with_goals == totals.levels {
+        1
+    } else {
+        0
+    }
+}
+
+// if all but one level is complete, we can select which initial ending split criteria to use
+// for future me because i'm stupid: this is intentially equal to num_levels - 1, we don't want to update criteria during the last level
+// this will go away once first goal is done on last level, so make sure to retrieve from the last tick if prediction is invalid
+fn predict_stars(state: &alcatraz_utils::TourState, tour: usize) -> u8 {
+    let totals = &alcatraz_utils::TOUR_TOTALS[tour];
+    let goal_level = first_level_with(tour, |v| v.goals > 0);
+    let comp_level = first_level_with(tour, |v| v.gold_medals > 0);
+
+    if state.levels_with_goals == totals.levels - 1 {
+        // two possible 2+ star states: all but one goal level done, or all but one comp done
+        if state.goals == totals.goals - goal_level.goals && state.gold_medals == totals.gold_medals {
+            if state.pro_goals == totals.pro_goals - goal_level.pro_goals {
+                3
+            } else {
+                2
+            }
+        } else if state.goals == totals.goals && state.gold_medals == totals.gold_medals - comp_level.gold_medals {
+            if state.pro_goals == totals.pro_goals - comp_level.pro_goals {
+                3
+            } else {
+                2
+            }
+        } else {
+            1
+        }
+    } else {
+        0
+    }
+}
+
+pub struct State {
     level_name: String,
     goal_count: u32,
     thps3_clear_prediction: u8,
     thps4_clear_prediction: u8,
     thps3_stars: u8,
     thps4_stars: u8,
+    total_goal_count: u32,
+    thps3_skaters: u32,
+    thps4_skaters: u32,
+    run_time: f32,
+    is_run_clock_running: bool,
     gamemode: u8,
     is_running: bool,
     is_loading: bool,
@@ -21,82 +78,12 @@ impl State {
         let thps3state = career.get_tour_state(2);
         let thps4state = career.get_tour_state(3);
 
-        // if all but one level is complete, we can select which initial ending split criteria to use
-        // for future me because i'm stupid: this is intentially equal to num_levels - 1, we don't want to update criteria during the last level
-        // this will go away once first goal is done on last level, so make sure to retrieve from the last tick if prediction is invalid
-        let thps3_clear_prediction = if thps3state.levels_with_goals == 8 {
-            // two possible 2+ star states: all but one goal level done, or all but one comp done
-            if thps3state.goals == 50 && thps3state.gold_medals == 3 {
-                if thps3state.pro_goals == 28 {
-                    3
-                } else {
-                    2
-                }
-            } else if thps3state.goals == 60 && thps3state.gold_medals == 2 {
-                if thps3state.pro_goals == 32 {
-                    3
-                } else {
-                    2
-                }
-            } else {
-                1
-            }
-        } else {
-            0
-        };
-
-        let thps4_clear_prediction = if thps4state.levels_with_goals == 9 {
-            // two possible 2+ star states: all but one goal level done, or all but one comp done
-            if thps4state.goals == 60 && thps4state.gold_medals == 3 {
-                if thps4state.pro_goals == 33 {
-                    3
-                } else {
-                    2
-                }
-            } else if thps4state.goals == 70 && thps4state.gold_medals == 2 {
-                if thps4state.pro_goals == 37 {
-                    3
-                } else {
-                    2
-                }
-            } else {
-                1
-            }
-        } else {
-            0
-        };
+        let thps3_clear_prediction = predict_stars(thps3state, 2);
+        let thps4_clear_prediction = predict_stars(thps4state, 3);
+        let thps3_stars = count_stars(thps3state, 2);
+        let thps4_stars = count_stars(thps4state, 3);
 
-        // check thps3 stars
-        let thps3_stars = if thps3state.goals == 60 && thps3state.gold_medals == 3 {
-            if thps3state.pro_goals == 33 {
-                3
-            } else {
-                2
-            }
-        } else {
-            if thps3state.levels_with_goals == 9 {
-                1
-            } else {
-                0
-            }
-        };
-
-        // check thps4 stars
-        let thps4_stars = if thps4state.goals == 70 && thps4state.gold_medals == 3 {
-            if thps4state.pro_goals == 38 {
-                3
-            } else {
-                2
-            }
-        } else {
-            if thps4state.levels_with_goals == 10 {
-                1
-            } else {
-                0
-            }
-        };
-
-        //asr::print_message(format!("3 GOALS: {}, 3 GOLDS: {}, 4 GOALS: {}, 4 GOLDS: {}", thps3state.goals, thps3state.gold_medals, thps4state.goals, thps4state.gold_medals).as_str());
+        trace!("THPS3 goals: {}, golds: {}, not in the table: {}, THPS4 goals: {}, golds: {}, not in the table: {}", thps3state.goals, thps3state.gold_medals, thps3state.inferred_goals, thps4state.goals, thps4state.gold_medals, thps4state.inferred_goals);
 
         Self {
             level_name: context.get_level_name(process),
@@ -105,148 +92,241 @@ impl State {
             thps4_clear_prediction,
             thps3_stars,
             thps4_stars,
+            total_goal_count: career.get_total_goal_count(),
+            thps3_skaters: career.get_skaters_with_completed_tour(2, |v| count_stars(v, 2) >= 2),
+            thps4_skaters: career.get_skaters_with_completed_tour(3, |v| count_stars(v, 3) >= 2),
+            run_time: context.get_run_time(),
+            is_run_clock_running: context.is_run_clock_running(),
             gamemode: context.get_gamemode(process),
             is_running: context.is_run_active(process),
             is_loading: context.is_loading(process),
         }
     }
-}
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THPS3+4!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        // the level name is briefly empty while loading, so keep showing the last one
+        if !self.level_name.is_empty() && self.level_name != "None" {
+            variables.set("Level", &self.level_name);
+        }
 
-    let base_addr = process.get_module_address(process_name).unwrap();
+        variables.set("Goals", self.goal_count);
+        variables.set("THPS3 Stars", self.thps3_stars);
+        variables.set("THPS4 Stars", self.thps4_stars);
+        variables.set("Total Goals", self.total_goal_count);
+        variables.set("THPS3 Skaters", self.thps3_skaters);
+        variables.set("THPS4 Skaters", self.thps4_skaters);
+    }
+}
 
-    let context;
-    loop {
-        asr::print_message("Finding offsets...");
-        let context_result = alcatraz_utils::AlcatrazContext::new(process, base_addr, alcatraz_utils::Game::THPS34);
-        if let Some(ctx) = context_result {
-            asr::print_message("Offsets found!");
-
-            ctx.list_addresses();
-
-            context = ctx;
-            break;
-        } 
-        
-        asr::print_message("Failed to find offsets! Trying again...");
-        asr::future::next_tick().await;
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("level", &self.level_name);
+        w.field("goals", self.goal_count);
+        w.field("thps3_prediction", self.thps3_clear_prediction);
+        w.field("thps4_prediction", self.thps4_clear_prediction);
+        w.field("thps3_stars", self.thps3_stars);
+        w.field("thps4_stars", self.thps4_stars);
+        w.field("total_goals", self.total_goal_count);
+        w.field("thps3_skaters", self.thps3_skaters);
+        w.field("thps4_skaters", self.thps4_skaters);
+        w.field("run_time", self.run_time);
+        w.field("run_clock_running", self.is_run_clock_running);
+        w.field("mode", self.gamemode);
+        w.field("running", self.is_running);
+        w.field("loading", self.is_loading);
     }
 
-    let mut career = context.get_career_state(process);
-    let mut prev_state = State::update(process, &context, &mut career);
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            level_name: r.field("level")?,
+            goal_count: r.field("goals")?,
+            thps3_clear_prediction: r.field("thps3_prediction")?,
+            thps4_clear_prediction: r.field("thps4_prediction")?,
+            thps3_stars: r.field("thps3_stars")?,
+            thps4_stars: r.field("thps4_stars")?,
+            total_goal_count: r.field("total_goals")?,
+            thps3_skaters: r.field("thps3_skaters")?,
+            thps4_skaters: r.field("thps4_skaters")?,
+            run_time: r.field("run_time")?,
+            is_run_clock_running: r.field("run_clock_running")?,
+            gamemode: r.field("mode")?,
+            is_running: r.field("running")?,
+            is_loading: r.field("loading")?,
+        })
+    }
+}
 
-    let mut starting_game = false;
-    let mut ignore_next_level = false;
-    let mut pending_split = false;
+// the level name reads as empty or "None" while loading
+fn level_name(state: &State) -> Option<String> {
+    Some(state.level_name.clone()).filter(|v| !v.is_empty() && v != "None")
+}
 
-    loop {
-        // update vars
-        let mut current_state = State::update(process, &context, &mut career);
+pub struct Splitter {
+    prev_state: State,
+    level_name: Watcher<String>,
+    thps3_clear_prediction: Watcher<u8>,
+    thps4_clear_prediction: Watcher<u8>,
+    continuing_series: bool,
+    starting_game: bool,
+    ignore_next_level: bool,
+    pending_split: bool,
+    run_time: alcatraz_utils::RunTimeAccumulator,
+    // when continuing a series, the timer already holds the earlier games' time and setting game time would throw it away.
+    // game time is left running only while the run clock counts instead, so this game's runs are added on top
+    igt_by_pausing: bool,
+    is_counting: bool,
+}
 
-        // if we see an invalid level name, fill in the previous
-        if current_state.level_name.is_empty() || current_state.level_name == "None" {
-            current_state.level_name = prev_state.level_name.clone();
-        }
+impl GameSplitter for Splitter {
+    type State = State;
 
-        // restore star prediction if not present
-        if current_state.thps3_clear_prediction == 0 {
-            current_state.thps3_clear_prediction = prev_state.thps3_clear_prediction;
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            // a series run can attach with a level already loaded, which shouldn't look like a level change on the first tick
+            level_name: Watcher::holding_from(level_name(&initial_state).unwrap_or_default()),
+            prev_state: initial_state,
+            thps3_clear_prediction: Watcher::holding(),
+            thps4_clear_prediction: Watcher::holding(),
+            continuing_series,
+            starting_game: false,
+            ignore_next_level: false,
+            pending_split: false,
+            run_time: alcatraz_utils::RunTimeAccumulator::new(),
+            igt_by_pausing: continuing_series,
+            is_counting: continuing_series, // the series resumed game time for us
         }
+    }
 
-        if current_state.thps4_clear_prediction == 0 {
-            current_state.thps4_clear_prediction = prev_state.thps4_clear_prediction;
-        }
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, mut current_state: State) {
+        // the level name reads as empty or "None" while loading, so hold on to the last real one
+        current_state.level_name = self.level_name.update(level_name(&current_state)).current().clone();
+
+        // the star predictions are 0 when not present, so keep the last ones
+        current_state.thps3_clear_prediction = *self.thps3_clear_prediction.update(Some(current_state.thps3_clear_prediction).filter(|v| *v != 0)).current();
+        current_state.thps4_clear_prediction = *self.thps4_clear_prediction.update(Some(current_state.thps4_clear_prediction).filter(|v| *v != 0)).current();
+
+        let prev_state = &self.prev_state;
 
         // update career
-        if current_state.goal_count != prev_state.goal_count {
-            //asr::print_message(format!("GOAL COUNT CHANGED TO {}", current_state.goal_count).as_str());
-            if current_state.goal_count < prev_state.goal_count {
-                starting_game = false;
+        let goal_count = Watcher::between(prev_state.goal_count, current_state.goal_count);
+        if goal_count.changed() {
+            debug!("Goal count changed to {}", current_state.goal_count);
+            if goal_count.current() < goal_count.old() {
+                self.starting_game = false;
             }
         }
 
-        // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+        // pause game time when loading, resume when done.  when timing by the sum of run times, game time follows the run clock instead
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
+        if settings.thps34_timing == AlcatrazTiming::LoadRemoved {
+            if is_loading.changed_to(&true) {
+                timer.pause_game_time();
+                debug!("Starting Load...");
+            } else if is_loading.changed_to(&false) {
+                timer.resume_game_time();
+                debug!("Done Loading");
+            }
         }
 
-        if (current_state.level_name == "Foundry" || current_state.level_name == "College") && prev_state.level_name == "FrontEnd" {
-            starting_game = true;
-            asr::print_message(format!("Starting a game").as_str());
+        if (current_state.level_name == "Foundry" || current_state.level_name == "College") && self.level_name.old() == "FrontEnd" {
+            self.starting_game = true;
+            debug!("Starting a game");
         }
 
-        if starting_game && (current_state.level_name != "Foundry" && current_state.level_name != "College") {
-            starting_game = false;
-            asr::print_message(format!("...or not starting a game").as_str());
+        if self.starting_game && (current_state.level_name != "Foundry" && current_state.level_name != "College") {
+            self.starting_game = false;
+            debug!("...or not starting a game");
         }
 
-        match asr::timer::state() {
+        match timer.state() {
             TimerState::NotRunning => {
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+                self.igt_by_pausing = false;
+
                 // start when no goals have been completed and starting a first level
-                if starting_game && current_state.goal_count == 0 && current_state.is_running {
-                    if current_state.gamemode == 0x02 {
-                        asr::timer::start();
-                        asr::print_message(format!("Starting timer...").as_str());
+                if self.starting_game && current_state.goal_count == 0 && current_state.is_running {
+                    if settings.thps34_start && current_state.gamemode == 0x02 {
+                        timer.start();
+                        info!("Starting timer...");
+
+                        // game time is set from the run clock, so it shouldn't also run on its own
+                        if settings.thps34_timing == AlcatrazTiming::SumOfRuns {
+                            timer.pause_game_time();
+                            self.run_time.reset();
+                        }
                     }
-                    starting_game = false;
-                    pending_split = false;
-                    ignore_next_level = false;
+                    self.starting_game = false;
+                    self.pending_split = false;
+                    self.ignore_next_level = false;
                 }
             },
             TimerState::Paused | TimerState::Running => {
                 // split on level changes (except frontend)
-                if !starting_game && !current_state.level_name.is_empty() && current_state.level_name != prev_state.level_name && current_state.level_name != "FrontEnd" {
-                    if !ignore_next_level {
-                        asr::timer::split();
-                        asr::print_message(format!("Changed level; splitting timer...").as_str());
+                if settings.thps34_split_level && !self.starting_game && !current_state.level_name.is_empty() && self.level_name.changed() && current_state.level_name != "FrontEnd" {
+                    if !self.ignore_next_level {
+                        timer.split();
+                        info!("Changed level; splitting timer...");
                     } else {
-                        asr::print_message("Changed level; Ignoring level split!");
-                        ignore_next_level = false;
+                        info!("Changed level; Ignoring level split!");
+                        self.ignore_next_level = false;
                     }
                 }
 
                 // split when second game is started
-                if ((current_state.thps3_stars > 0 && current_state.level_name == "College") || (current_state.thps4_stars > 0 && current_state.level_name == "Foundry")) && starting_game && current_state.is_running {
+                if ((current_state.thps3_stars > 0 && current_state.level_name == "College") || (current_state.thps4_stars > 0 && current_state.level_name == "Foundry")) && self.starting_game && current_state.is_running {
                     if current_state.gamemode == 0x02 {
-                        asr::timer::split();
-                        asr::print_message(format!("Changed level; splitting timer...").as_str());
+                        timer.split();
+                        info!("Changed level; splitting timer...");
                     }
-                    starting_game = false;
+                    self.starting_game = false;
                 }
 
                 // split when all thps3 goals and golds are complete
                 if current_state.thps3_stars > prev_state.thps3_stars && current_state.thps3_stars >= current_state.thps3_clear_prediction {
-                    pending_split = true;
-                    asr::print_message(format!("THPS3 {} star; ready to split...", current_state.thps3_stars).as_str());
+                    self.pending_split = true;
+                    debug!("THPS3 {} star; ready to split...", current_state.thps3_stars);
                 }
 
                 // split when all thps4 goals and golds are complete
                 if current_state.thps4_stars > prev_state.thps4_stars && current_state.thps4_stars >= current_state.thps4_clear_prediction {
-                    pending_split = true;
-                    asr::print_message(format!("THPS4 {} star; ready to split...", current_state.thps4_stars).as_str());
+                    self.pending_split = true;
+                    debug!("THPS4 {} star; ready to split...", current_state.thps4_stars);
                 }
 
-                if !current_state.is_running && pending_split {
-                    asr::timer::split();
-                    asr::print_message(format!("Ended run for star; splitting timer...").as_str());
+                if settings.thps34_split_star && !current_state.is_running && self.pending_split {
+                    timer.split();
+                    info!("Ended run for star; splitting timer...");
                     
-                    pending_split = false;
+                    self.pending_split = false;
+                }
+
+                // add up the time of every run
+                if settings.thps34_timing == AlcatrazTiming::SumOfRuns {
+                    if self.igt_by_pausing {
+                        let is_counting = current_state.is_run_clock_running;
+                        if is_counting && !self.is_counting {
+                            timer.resume_game_time();
+                        } else if !is_counting && self.is_counting {
+                            timer.pause_game_time();
+                        }
+                        self.is_counting = is_counting;
+                    } else if let Some(igt) = self.run_time.update(current_state.is_running, current_state.run_time) {
+                        timer.set_game_time(igt);
+                    }
                 }
 
                 // reset when on frontend with 0 pro points
-                if current_state.level_name == "FrontEnd" && current_state.goal_count == 0 {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
+                if settings.thps34_reset && !self.continuing_series && current_state.level_name == "FrontEnd" && current_state.goal_count == 0 {
+                    timer.reset();
+                    info!("Resetting timer...");
 
-                    pending_split = false;
-                    ignore_next_level = false;
+                    if settings.thps34_timing == AlcatrazTiming::SumOfRuns {
+                        timer.resume_game_time();
+                    }
+
+                    self.pending_split = false;
+                    self.ignore_next_level = false;
                 }
             },
             TimerState::Ended | TimerState::Unknown | _ => {
@@ -254,7 +334,39 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THPS3+4!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let base_addr = attach::module_address(process, process_name).await;
+
+    info!("Finding offsets...");
+    let mut context = attach::retry("offsets", || alcatraz_utils::AlcatrazContext::new(process, base_addr, alcatraz_utils::Game::THPS34)).await;
+
+    info!("Offsets found!");
+    context.list_addresses();
+
+    let mut career = context.get_career_state(process);
+    let mut splitter = Splitter::new(State::update(process, &context, &mut career), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THPS34");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit64);
+
+    loop {
+        settings.refresh();
+        context.refresh(process);
+
+        // update vars
+        let current_state = State::update(process, &context, &mut career);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
@@ -1,6 +1,33 @@
 use asr::{Address, Process, timer::TimerState};
 
-struct State {
+use crate::{attach, memory::MemorySource, research::Researcher, scan::{self, RootSignature}, settings::Settings, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
+
+#[derive(Copy, Clone)]
+pub struct Offsets {
+    career: u32,
+    loading: u32,
+}
+
+static OFFSETS: Offsets = Offsets {
+    career: 0x6B5B48,
+    loading: 0x6728C0,
+};
+
+// code that references each root pointer.  none have been mapped out for THPS4 yet, so these are the places to add them
+static CAREER_SIGNATURES: &[RootSignature] = &[];
+static LOADING_SIGNATURES: &[RootSignature] = &[];
+
+impl Offsets {
+    // each pointer from a signature scan, or the stock exe's offset if none match
+    pub fn get(process: &Process, base_addr: Address, module_size: u64) -> Self {
+        Self {
+            career: scan::resolve_or(process, base_addr, module_size, "the career pointer", CAREER_SIGNATURES, OFFSETS.career),
+            loading: scan::resolve_or(process, base_addr, module_size, "the loading flag", LOADING_SIGNATURES, OFFSETS.loading),
+        }
+    }
+}
+
+pub struct State {
     level_id: u8,
     total_cash: u32,
     pro_points: u8,
@@ -10,94 +37,134 @@ struct State {
 
 // TODO: add splits for other categories!!
 
-impl State {
-    pub fn update(process: &Process, base_addr: Address) -> Self {
-        State {
+pub struct Watchers {
+    level_id: MemoryWatcher<u8>,
+    total_cash: MemoryWatcher<u32>,
+    pro_points: MemoryWatcher<u8>,
+    pro_goals: MemoryWatcher<u32>,
+    is_loading: MemoryWatcher<bool>,
+}
+
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
             // TODO: change this to use level ID
-            level_id: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x6B5B48 as u64, 0x20 as u64, 0x484 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+            level_id: MemoryWatcher::new(&[offsets.career as u64, 0x20, 0x484]),
+            total_cash: MemoryWatcher::new(&[offsets.career as u64, 0x86c, 0x28]),
+            pro_points: MemoryWatcher::new(&[offsets.career as u64, 0x86c, 0x20]),
+            pro_goals: MemoryWatcher::new(&[offsets.career as u64, 0x20, 0x454]),
+            is_loading: MemoryWatcher::new(&[offsets.loading as u64]),
+        }
+    }
+}
 
-            total_cash: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x6B5B48 as u64, 0x86c as u64, 0x28 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+impl State {
+    pub fn update(process: &impl MemorySource, base_addr: Address, watchers: &mut Watchers) -> Self {
+        State {
+            level_id: *watchers.level_id.update(process, base_addr).current(),
+            total_cash: *watchers.total_cash.update(process, base_addr).current(),
+            pro_points: *watchers.pro_points.update(process, base_addr).current(),
+            pro_goals_completed: watchers.pro_goals.update(process, base_addr).current().count_ones() as u8,
+            is_loading: *watchers.is_loading.update(process, base_addr).current(),
+        }
+    }
 
-            pro_points: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x6B5B48 as u64, 0x86c as u64, 0x20 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Cash", self.total_cash);
+        variables.set("Pro Points", self.pro_points);
+        variables.set("Pro Goals", self.pro_goals_completed);
+    }
+}
 
-            pro_goals_completed: match process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x6B5B48 as u64, 0x20 as u64, 0x454 as u64)) {
-                Ok(v) => v.count_ones() as u8,
-                Err(_) => 0,
-            },
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("level", self.level_id);
+        w.field("cash", self.total_cash);
+        w.field("pro_points", self.pro_points);
+        w.field("pro_goals", self.pro_goals_completed);
+        w.field("loading", self.is_loading);
+    }
 
-            is_loading: match process.read::<bool>(base_addr + 0x6728C0 as u32) {
-                Ok(v) => v,
-                Err(_) => false,
-            }
-        }
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            level_id: r.field("level")?,
+            total_cash: r.field("cash")?,
+            pro_points: r.field("pro_points")?,
+            pro_goals_completed: r.field("pro_goals")?,
+            is_loading: r.field("loading")?,
+        })
     }
 }
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THPS4!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+}
 
-    let base_addr = process.get_module_address(process_name).unwrap();
+impl GameSplitter for Splitter {
+    type State = State;
 
-    let mut prev_state = State::update(process, base_addr);
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+        }
+    }
 
-    loop {
-        // update vars
-        let current_state = State::update(process, base_addr);
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, current_state: State) {
+        let prev_state = &self.prev_state;
 
         // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
+        if is_loading.changed_to(&true) {
+            timer.pause_game_time();
+            debug!("Starting Load...");
+        } else if is_loading.changed_to(&false) {
+            timer.resume_game_time();
+            debug!("Done Loading");
         }
 
-        match asr::timer::state() {
+        let level_id = Watcher::between(prev_state.level_id, current_state.level_id);
+
+        match timer.state() {
             TimerState::NotRunning => {
-                if current_state.level_id == 1 && prev_state.level_id == 0 && current_state.pro_points == 0 {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer...").as_str());
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+
+                if settings.thps4_start && *level_id.old() == 0 && level_id.changed_to(&1) && current_state.pro_points == 0 {
+                    timer.start();
+                    info!("Starting timer...");
                 }
             },
             TimerState::Paused | TimerState::Running => {
                 // split on level changes (except skateshop)
-                if current_state.level_id != prev_state.level_id && current_state.level_id != 0 {
-                    asr::timer::split();
-                    asr::print_message(format!("Changed level; splitting timer...").as_str());
+                if settings.thps4_split_level && level_id.changed() && current_state.level_id != 0 {
+                    timer.split();
+                    info!("Changed level; splitting timer...");
                 }
 
-                if current_state.pro_goals_completed > prev_state.pro_goals_completed && prev_state.pro_goals_completed == 0 {
-                    asr::timer::split();
-                    asr::print_message(format!("Completed pro goal; splitting timer...").as_str());
+                if settings.thps4_split_pro_goal && current_state.pro_goals_completed > prev_state.pro_goals_completed && prev_state.pro_goals_completed == 0 {
+                    timer.split();
+                    info!("Completed pro goal; splitting timer...");
                 }
 
                 // split when all goals cleared (190 pro points)
-                if current_state.pro_points != prev_state.pro_points && current_state.pro_points == 190 {
-                    asr::timer::split();
-                    asr::print_message(format!("Completed all goals; splitting timer...").as_str());
+                if settings.thps4_split_all_goals && Watcher::between(prev_state.pro_points, current_state.pro_points).rose_to(&190) {
+                    timer.split();
+                    info!("Completed all goals; splitting timer...");
                 }
 
                 // split on all cash collected
-                if current_state.total_cash != prev_state.total_cash && current_state.total_cash == 100000 {
-                    asr::timer::split();
-                    asr::print_message(format!("All cash collected; splitting timer...").as_str());
+                if settings.thps4_split_all_cash && Watcher::between(prev_state.total_cash, current_state.total_cash).rose_to(&100000) {
+                    timer.split();
+                    info!("All cash collected; splitting timer...");
                 }
 
                 // reset when on skateshop with 0 pro points
-                if current_state.level_id == 0 && current_state.pro_points == 0 {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
+                if settings.thps4_reset && !self.continuing_series && current_state.level_id == 0 && current_state.pro_points == 0 {
+                    timer.reset();
+                    info!("Resetting timer...");
                 }
             },
             TimerState::Ended | TimerState::Unknown | _ => {
@@ -105,8 +172,119 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THPS4!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let (base_addr, module_size) = attach::module_range(process, process_name).await;
+    let offsets = Offsets::get(process, base_addr, module_size);
+
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THPS4");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
+
+    loop {
+        settings.refresh();
+
+        // update vars
+        let current_state = State::update(process, base_addr, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use asr::PointerSize;
+    use bytemuck::NoUninit;
+
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
+
+    const BASE: u64 = 0x400000;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        offsets: Offsets,
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+    }
+
+    impl Game {
+        fn new() -> Self {
+            let offsets = OFFSETS;
+            let memory = MockMemory::new();
+            let mut watchers = Watchers::new(&offsets);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE), &mut watchers), false);
+
+            Self { offsets, memory, watchers, splitter, timer: ReplayTimer::new(None) }
+        }
+
+        fn set<T: NoUninit>(&mut self, path: &[u64], value: T) {
+            self.memory.write_pointer_path(Address::new(BASE), PointerSize::Bit32, path, value);
+        }
+
+        fn set_career<T: NoUninit>(&mut self, path: &[u64], value: T) {
+            self.set(&[&[self.offsets.career as u64][..], path].concat(), value);
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = State::update(&self.memory, Address::new(BASE), &mut self.watchers);
+            self.splitter.update(&mut self.timer, &Settings::default(), state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_on_the_first_level_and_splits_on_level_changes() {
+        let mut game = Game::new();
+        game.set_career(&[0x20, 0x484], 0u8);
+        assert!(game.tick().is_empty());
+
+        game.set_career(&[0x20, 0x484], 1u8);
+        assert_eq!(game.tick(), [Start]);
+
+        game.set(&[game.offsets.loading as u64], 1u8);
+        assert_eq!(game.tick(), [PauseGameTime]);
+
+        game.set(&[game.offsets.loading as u64], 0u8);
+        game.set_career(&[0x20, 0x484], 2u8);
+        assert_eq!(game.tick(), [ResumeGameTime, Split]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn splits_once_when_all_goals_are_done() {
+        let mut game = Game::new();
+        game.set_career(&[0x20, 0x484], 1u8);
+        game.set_career(&[0x86c, 0x20], 189u8);
+        assert!(game.tick().is_empty());    // not a new career, so the timer doesn't start
+
+        game.timer.start();
+        game.set_career(&[0x86c, 0x20], 190u8);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn resets_in_the_skateshop_without_pro_points() {
+        let mut game = Game::new();
+        game.timer.start();
+        assert_eq!(game.tick(), [Reset]);
+    }
+}
@@ -2,7 +2,38 @@ use std::u64;
 
 use asr::{Address, Process, timer::TimerState, string::ArrayCString};
 
-struct State {
+use crate::{attach, memory::MemorySource, research::Researcher, scan::{self, RootSignature}, settings::Settings, split_rules::{SplitRule, SplitRules}, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
+
+#[derive(Copy, Clone)]
+pub struct Offsets {
+    intro_movie: u32,
+    career: u32,
+    loading: u32,
+}
+
+static OFFSETS: Offsets = Offsets {
+    intro_movie: 0x36A7C8,
+    career: 0x36A788,
+    loading: 0x29851C,
+};
+
+// code that references each root pointer.  none have been mapped out for THUG1 yet, so these are the places to add them
+static INTRO_MOVIE_SIGNATURES: &[RootSignature] = &[];
+static CAREER_SIGNATURES: &[RootSignature] = &[];
+static LOADING_SIGNATURES: &[RootSignature] = &[];
+
+impl Offsets {
+    // each pointer from a signature scan, or the stock exe's offset if none match
+    pub fn get(process: &Process, base_addr: Address, module_size: u64) -> Self {
+        Self {
+            intro_movie: scan::resolve_or(process, base_addr, module_size, "the intro movie flag", INTRO_MOVIE_SIGNATURES, OFFSETS.intro_movie),
+            career: scan::resolve_or(process, base_addr, module_size, "the career pointer", CAREER_SIGNATURES, OFFSETS.career),
+            loading: scan::resolve_or(process, base_addr, module_size, "the loading flag", LOADING_SIGNATURES, OFFSETS.loading),
+        }
+    }
+}
+
+pub struct State {
     has_played_intro: bool,
     level_id: u8,
     _goal_count: u8,
@@ -11,15 +42,37 @@ struct State {
     is_career_started: bool,
 }
 
+pub struct Watchers {
+    intro_movie: u32,
+    level_id: MemoryWatcher<u8>,
+    career_flags: MemoryWatcher<u8>,
+    goal_count: MemoryWatcher<u8>,
+    chapter: MemoryWatcher<u8>,
+    is_loading: MemoryWatcher<bool>,
+}
+
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
+            intro_movie: offsets.intro_movie,
+            level_id: MemoryWatcher::new(&[offsets.career as u64, 0x20, 0x5c4]),
+            career_flags: MemoryWatcher::new(&[offsets.career as u64, 0x20, 0x592]),
+            goal_count: MemoryWatcher::new(&[offsets.career as u64, 0x3a8, 0x24]),
+            chapter: MemoryWatcher::new(&[offsets.career as u64, 0x3a8, 0x3c]),
+            is_loading: MemoryWatcher::new(&[offsets.loading as u64, 0x24, 0x174]),
+        }
+    }
+}
+
 impl State {
-    pub fn update(process: &Process, base_addr: Address) -> Self {
+    pub fn update(process: &impl MemorySource, base_addr: Address, watchers: &mut Watchers) -> Self {
         State {
-            has_played_intro: match process.read::<ArrayCString<16>>(base_addr + 0x36A7C8 as u32) {
+            has_played_intro: match process.read::<ArrayCString<16>>(base_addr + watchers.intro_movie) {
                 Ok(v) => {
                     match String::from_utf8(v.as_bytes().to_vec()) {
                         Ok(v) => v == "Intro_02",
                         Err(err) => {
-                            asr::print_message(format!("Error reading last cutscene name: {:?}", err).as_str());
+                            warn!("Error reading last cutscene name: {:?}", err);
                             false
                         },
                     }
@@ -27,166 +80,291 @@ impl State {
                 Err(_) => false,
             },
 
-            level_id: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x36A788 as u64, 0x20 as u64, 0x5c4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            is_career_started: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x36A788 as u64, 0x20 as u64, 0x592 as u64)) {
-                Ok(v) => (v & 0x8) != 0,
-                Err(_) => false,
-            },
+            level_id: *watchers.level_id.update(process, base_addr).current(),
+            is_career_started: (watchers.career_flags.update(process, base_addr).current() & 0x8) != 0,
+            _goal_count: *watchers.goal_count.update(process, base_addr).current(),
+            chapter: *watchers.chapter.update(process, base_addr).current(),
+            is_loading: *watchers.is_loading.update(process, base_addr).current(),
+        }
+    }
 
-            _goal_count: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x36A788 as u64, 0x3a8 as u64, 0x24 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Chapter", self.chapter);
+    }
+}
 
-            chapter: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x36A788 as u64, 0x3a8 as u64, 0x3c as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("intro", self.has_played_intro);
+        w.field("level", self.level_id);
+        w.field("goals", self._goal_count);
+        w.field("chapter", self.chapter);
+        w.field("loading", self.is_loading);
+        w.field("career_started", self.is_career_started);
+    }
 
-            is_loading: match process.read_pointer_path::<bool>(base_addr, asr::PointerSize::Bit32, &vec!(0x29851C as u64, 0x24 as u64, 0x174 as u64)) {
-                Ok(v) => v,
-                Err(_) => false,
-            }
-        }
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            has_played_intro: r.field("intro")?,
+            level_id: r.field("level")?,
+            _goal_count: r.field("goals")?,
+            chapter: r.field("chapter")?,
+            is_loading: r.field("loading")?,
+            is_career_started: r.field("career_started")?,
+        })
     }
 }
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THUG1!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+// story splits happen when each chapter starts, while preserving timing
+static STORY_RULES: &[SplitRule<State>] = &[
+    SplitRule {
+        name: "Started Manhattan",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 3 && s.level_id == 2,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Tampa",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 6 && s.level_id == 3,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started San Diego",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 10 && s.level_id == 4,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Hawaii",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 13 && s.level_id == 5,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Vancouver",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 16 && s.level_id == 6,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Slam City Jam",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 17 && s.level_id == 7,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Vancouver 2",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 18 && s.level_id == 6,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Moscow",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 19 && s.level_id == 8,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started New Jersey 2",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 22 && s.level_id == 1,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Pro Goals",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 25 && s.level_id != 20,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Started Eric's Line",
+        enabled: |s| s.thug1_split_chapter,
+        condition: |_, s| s.chapter == 26 && s.level_id == 1,
+        rearm: None,
+        primed: None,
+    },
+    SplitRule {
+        name: "Finished Story",
+        enabled: |s| s.thug1_split_final,
+        condition: |_, s| s.chapter == 27,
+        rearm: None,
+        primed: None,
+    },
+];
 
-    let base_addr = process.get_module_address(process_name).unwrap();
-
-    let mut prev_state = State::update(process, base_addr);
-
-    // these are used to prevent double splitting while preserving timing
-    let mut story_flags = [
-        false, // manhattan
-        false, // tampa
-        false, // san diego
-        false, // hawaii
-        false, // vancouver
-        false, // slam city jam
-        false, // vancouver 2
-        false, // moscow
-        false, // new jersey 2
-        false, // pro goals
-        false, // eric's line
-        false, // eric's line done
-    ];
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+    story: SplitRules<State>,
+}
 
-    loop {
-        // update vars
-        let current_state = State::update(process, base_addr);
+impl GameSplitter for Splitter {
+    type State = State;
+
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+            story: SplitRules::new(STORY_RULES),
+        }
+    }
+
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, current_state: State) {
+        let prev_state = &self.prev_state;
 
         // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
+        if is_loading.changed_to(&true) {
+            timer.pause_game_time();
+            debug!("Starting Load...");
+        } else if is_loading.changed_to(&false) {
+            timer.resume_game_time();
+            debug!("Done Loading");
         }
 
-        match asr::timer::state() {
+        match timer.state() {
             TimerState::NotRunning => {
-                story_flags.fill(false);
-                if current_state.has_played_intro && !prev_state.has_played_intro {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer...").as_str());
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+
+                self.story.reset();
+                if settings.thug1_start && Watcher::between(prev_state.has_played_intro, current_state.has_played_intro).changed_to(&true) {
+                    timer.start();
+                    info!("Starting timer...");
                 }
             },
             TimerState::Paused | TimerState::Running => {
-                if !story_flags[0] && current_state.chapter == 3 && current_state.level_id == 2 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Manhattan; splitting timer...").as_str());
-                    story_flags[0] = true;
-                }
+                self.story.update(timer, settings, prev_state, &current_state);
 
-                if !story_flags[1] && current_state.chapter == 6 && current_state.level_id == 3 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Tampa; splitting timer...").as_str());
-                    story_flags[1] = true;
+                // reset when on main menu with a career not started
+                if settings.thug1_reset && !self.continuing_series && current_state.level_id == 0 && !current_state.is_career_started {
+                    timer.reset();
+                    info!("Resetting timer...");
+                    self.story.reset();
                 }
+            },
+            TimerState::Ended | TimerState::Unknown | _ => {
+                // do nothing. maybe we should still run reset when it's ended?
+            },
+        }
 
-                if !story_flags[2] && current_state.chapter == 10 && current_state.level_id == 4 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started San Diego; splitting timer...").as_str());
-                    story_flags[2] = true;
-                }
+        self.prev_state = current_state;
+    }
+}
 
-                if !story_flags[3] && current_state.chapter == 13 && current_state.level_id == 5 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Hawaii; splitting timer...").as_str());
-                    story_flags[3] = true;
-                }
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THUG1!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
 
-                if !story_flags[4] && current_state.chapter == 16 && current_state.level_id == 6 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Vancouver; splitting timer...").as_str());
-                    story_flags[4] = true;
-                }
+    let (base_addr, module_size) = attach::module_range(process, process_name).await;
+    let offsets = Offsets::get(process, base_addr, module_size);
 
-                if !story_flags[5] && current_state.chapter == 17 && current_state.level_id == 7 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Slam City Jam; splitting timer...").as_str());
-                    story_flags[5] = true;
-                }
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THUG1");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
 
-                if !story_flags[6] && current_state.chapter == 18 && current_state.level_id == 6 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Vancouver 2; splitting timer...").as_str());
-                    story_flags[6] = true;
-                }
+    loop {
+        settings.refresh();
 
-                if !story_flags[7] && current_state.chapter == 19 && current_state.level_id == 8 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Moscow; splitting timer...").as_str());
-                    story_flags[7] = true;
-                }
-                
-                if !story_flags[8] && current_state.chapter == 22 && current_state.level_id == 1 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started New Jersey 2; splitting timer...").as_str());
-                    story_flags[8] = true;
-                }
+        // update vars
+        let current_state = State::update(process, base_addr, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
 
-                if !story_flags[9] && current_state.chapter == 25 && current_state.level_id != 20 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Pro Goals; splitting timer...").as_str());
-                    story_flags[9] = true;
-                }
+        splitter.update(&mut AsrTimer, settings, current_state);
 
-                if !story_flags[10] && current_state.chapter == 26 && current_state.level_id == 1 {
-                    asr::timer::split();
-                    asr::print_message(format!("Started Eric's Line; splitting timer...").as_str());
-                    story_flags[10] = true;
-                }
+        asr::future::next_tick().await;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use asr::PointerSize;
+    use bytemuck::NoUninit;
 
-                if !story_flags[11] && current_state.chapter == 27 {
-                    asr::timer::split();
-                    asr::print_message(format!("Finished Story; splitting timer...").as_str());
-                    story_flags[11] = true;
-                }
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
 
-                // reset when on main menu with a career not started
-                if current_state.level_id == 0 && !current_state.is_career_started {
-                    asr::timer::reset();
-                    asr::print_message(format!("Resetting timer...").as_str());
-                    story_flags.fill(false);
-                }
-            },
-            TimerState::Ended | TimerState::Unknown | _ => {
-                // do nothing. maybe we should still run reset when it's ended?
-            },
+    const BASE: u64 = 0x400000;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        offsets: Offsets,
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+    }
+
+    impl Game {
+        fn new() -> Self {
+            let offsets = OFFSETS;
+            let memory = MockMemory::new();
+            let mut watchers = Watchers::new(&offsets);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE), &mut watchers), false);
+
+            Self { offsets, memory, watchers, splitter, timer: ReplayTimer::new(None) }
         }
 
-        prev_state = current_state;
+        fn set_career<T: NoUninit>(&mut self, path: &[u64], value: T) {
+            let path = [&[self.offsets.career as u64][..], path].concat();
+            self.memory.write_pointer_path(Address::new(BASE), PointerSize::Bit32, &path, value);
+        }
 
-        asr::future::next_tick().await;
+        fn set_cutscene(&mut self, name: &str) {
+            let mut bytes = [0u8; 16];
+            bytes[..name.len()].copy_from_slice(name.as_bytes());
+            self.memory.write(Address::new(BASE) + self.offsets.intro_movie, bytes);
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = State::update(&self.memory, Address::new(BASE), &mut self.watchers);
+            self.splitter.update(&mut self.timer, &Settings::default(), state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_after_the_intro_and_splits_on_each_chapter() {
+        let mut game = Game::new();
+        game.set_cutscene("Intro_01");
+        game.set_career(&[0x20, 0x592], 0x8u8);
+        assert!(game.tick().is_empty());
+
+        game.set_cutscene("Intro_02");
+        assert_eq!(game.tick(), [Start]);
+
+        game.set_career(&[0x20, 0x5c4], 2u8);
+        game.set_career(&[0x3a8, 0x3c], 3u8);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn resets_on_the_menu_without_a_career() {
+        let mut game = Game::new();
+        game.set_career(&[0x20, 0x592], 0x8u8);
+        game.timer.start();
+        assert!(game.tick().is_empty());
+
+        game.set_career(&[0x20, 0x592], 0u8);
+        assert_eq!(game.tick(), [Reset]);
+    }
+}
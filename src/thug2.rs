@@ -1,6 +1,39 @@
+use std::collections::HashSet;
+
 use asr::{Address, Process, timer::TimerState};
 
-struct State {
+use crate::{attach, checksum, memory::MemorySource, research::Researcher, scan::{self, RootSignature}, script::{ScriptStruct, ScriptValue}, settings::Settings, timer::{AsrTimer, Timer}, trace::{GameSplitter, Recorder, TraceReader, TraceState, TraceWriter}, variables::Variables, watcher::{MemoryWatcher, Watcher}};
+
+#[derive(Copy, Clone)]
+pub struct Offsets {
+    skate: u32,
+    run_ended: u32,
+    loading: u32,
+}
+
+static OFFSETS: Offsets = Offsets {
+    skate: 0x3ce478,
+    run_ended: 0x2F3624,
+    loading: 0x2FC49C,
+};
+
+// code that references each root pointer.  none have been mapped out for THUG2 yet, so these are the places to add them
+static SKATE_SIGNATURES: &[RootSignature] = &[];
+static RUN_ENDED_SIGNATURES: &[RootSignature] = &[];
+static LOADING_SIGNATURES: &[RootSignature] = &[];
+
+impl Offsets {
+    // each pointer from a signature scan, or the stock exe's offset if none match
+    pub fn get(process: &Process, base_addr: Address, module_size: u64) -> Self {
+        Self {
+            skate: scan::resolve_or(process, base_addr, module_size, "the skate pointer", SKATE_SIGNATURES, OFFSETS.skate),
+            run_ended: scan::resolve_or(process, base_addr, module_size, "the run ended flag", RUN_ENDED_SIGNATURES, OFFSETS.run_ended),
+            loading: scan::resolve_or(process, base_addr, module_size, "the loading flag", LOADING_SIGNATURES, OFFSETS.loading),
+        }
+    }
+}
+
+pub struct State {
     level_id: u8,
     total_classic_goals: u8,
     classic_triangle_goals: u8,
@@ -11,206 +44,208 @@ struct State {
     story_points: u16,
     _story_difficulty: Difficulty,
     classic_difficulty: Difficulty,
+    goals_read: bool,
+    completed_goals: HashSet<u32>,
+    story_goals: u32,
+    story_goal_count: u32,
+    open_story_goals: u32,
+    completed_classic_goals: u32,
 }
 
-struct ScriptStruct {
-    _unk: u32,
-    phead: u32,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)] 
+enum Difficulty {
+    UNKNOWN,
+    EASY,
+    NORMAL,
+    SICK,
 }
 
-impl ScriptStruct {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            _unk: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            phead: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+impl Difficulty {
+    fn from_id(id: u8) -> Self {
+        match id {
+            1 => Difficulty::EASY,
+            2 => Difficulty::NORMAL,
+            3 => Difficulty::SICK,
+            _ => Difficulty::UNKNOWN,
         }
     }
 }
 
-struct ScriptComponent {
-    _unk: u8,
-    _ttype: u8,
-    _size: u16,
-    name: u32,
-    data: u32,
-    pnext: u32,
+struct Goal {
+    mode: Gamemode,
+    complete: bool,
+    is_locked: bool,
+    unk2: u32,
 }
 
-impl ScriptComponent {
-    pub fn read(process: &Process, addr: u32) -> Self {
-        Self {
-            _unk: match process.read_pointer_path::<u8>(addr, asr::PointerSize::Bit32, &vec!(0x0 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            _ttype: match process.read_pointer_path::<u8>(addr, asr::PointerSize::Bit32, &vec!(0x1 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            _size: match process.read_pointer_path::<u16>(addr, asr::PointerSize::Bit32, &vec!(0x2 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            name: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            data: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0x8 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-            pnext: match process.read_pointer_path::<u32>(addr, asr::PointerSize::Bit32, &vec!(0xc as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+// the goal's mode is a checksum component holding either career or classic.  anything else (e.g. net goals) is skipped
+fn read_goal(goal: &ScriptStruct) -> Option<Goal> {
+    let mut mode = Gamemode::NONE;
+    let mut complete = false;
+    let mut is_locked = false;
+    let mut unk2 = 0;
+
+    for (name, value) in &goal.components {
+        if *name == checksum!("hasBeaten") {
+            if value.as_int() == Some(1) {
+                complete = true;
+            }
+        } else if *name == checksum!("isLocked") {
+            if value.as_int() == Some(1) {
+                is_locked = true;
+            }
+        } else if *name == 0x290a18e3 {    // name unknown
+            unk2 = value.as_int().unwrap_or(0) as u32;
+        } else if let Some(checksum) = value.as_checksum() {
+            if checksum == checksum!("career") {
+                mode = Gamemode::CAREER;
+            }
+            if checksum == checksum!("classic") {
+                mode = Gamemode::CLASSIC;
+            }
         }
     }
-}
 
-#[derive(Debug, PartialEq, Eq)] 
-enum Difficulty {
-    UNKNOWN,
-    EASY,
-    NORMAL,
-    SICK,
+    if !matches!(mode, Gamemode::NONE) {
+        Some(Goal {
+            mode,
+            complete,
+            is_locked,
+            unk2,
+        })
+    } else {
+        None
+    }
 }
 
-fn read_difficulty_levels(process: &Process, addr: u32) -> (Difficulty, Difficulty) {
-    let stru = ScriptStruct::read(process, addr);
-    let mut comp = stru.phead;
-    let mut story_difficulty = Difficulty::UNKNOWN;
-    let mut classic_difficulty = Difficulty::UNKNOWN;
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
-
-        if component.name == 0x4da4937b {   // "career"
-            if component.data == 0 {
-                story_difficulty = Difficulty::EASY;
-            } else if component.data == 1 {
-                story_difficulty = Difficulty::NORMAL;
-            } else if component.data == 2 {
-                story_difficulty = Difficulty::SICK;
-            }
-        } else if component.name == 0x4C10DE52 {    // "classic"
-            if component.data == 0 {
-                classic_difficulty = Difficulty::NORMAL;
-            } else if component.data == 1 {
-                classic_difficulty = Difficulty::SICK;
-            }
+// the goal flags struct is laid out the same as THAW's: goalmanager_params, then one struct per goal
+fn update_goal_flags(process: &impl MemorySource, addr: u32, state: &mut State) {
+    let Some(flags) = ScriptStruct::read(process, addr) else {
+        return;
+    };
+
+    state._story_difficulty = match flags.get(&[checksum!("goalmanager_params"), checksum!("difficulty_levels"), checksum!("career")]).and_then(ScriptValue::as_int) {
+        Some(0) => Difficulty::EASY,
+        Some(1) => Difficulty::NORMAL,
+        Some(2) => Difficulty::SICK,
+        _ => Difficulty::UNKNOWN,
+    };
+
+    state.classic_difficulty = match flags.get(&[checksum!("goalmanager_params"), checksum!("difficulty_levels"), checksum!("classic")]).and_then(ScriptValue::as_int) {
+        Some(0) => Difficulty::NORMAL,
+        Some(1) => Difficulty::SICK,
+        _ => Difficulty::UNKNOWN,
+    };
+
+    for (name, value) in &flags.components {
+        if *name == checksum!("goalmanager_params") {
+            continue;
         }
 
-        comp = component.pnext;
-    }
+        let Some(goal) = value.as_struct().and_then(read_goal) else {
+            continue;
+        };
 
-    (story_difficulty, classic_difficulty)
-}
+        let is_story = matches!(goal.mode, Gamemode::CAREER);
+        let is_complete = goal.complete && goal.unk2 == 1;
 
-fn read_goal_params(process: &Process, addr: u32) -> (Difficulty, Difficulty) {
-    let stru = ScriptStruct::read(process, addr);
-    let mut comp = stru.phead;
-    let mut result = (Difficulty::UNKNOWN, Difficulty::UNKNOWN);
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
+        if is_story {
+            state.story_goal_count += 1;
 
-        if component.name == 0xb13d98d3 {   // "difficulty_levels"
-            result = read_difficulty_levels(process, component.data);
+            if !is_complete && !goal.is_locked {
+                state.open_story_goals += 1;
+            }
         }
 
-        comp = component.pnext;
+        if is_complete {
+            state.completed_goals.insert(*name);
+
+            if is_story {
+                state.story_goals += 1;
+            } else {
+                state.completed_classic_goals += 1;
+            }
+        }
     }
 
-    result
+    state.goals_read = true;
 }
 
-fn get_difficulties(process: &Process, addr: u32) -> (Difficulty, Difficulty) {
-    let stru = ScriptStruct::read(process, addr);
-    let mut result = (Difficulty::UNKNOWN, Difficulty::UNKNOWN);
-    let mut comp = stru.phead;
-    while comp != 0 {
-        let component = ScriptComponent::read(process, comp);
-
-        if component.name == 0x23d4170a {   // "goalmanager_params"
-            result = read_goal_params(process, component.data);
-        } /*else if component.ttype >> 1 == 0xa {
-            if let Some(goal) = read_goal(process, component.data) {
-                if goal.complete && goal.unk2 == 1 && state.completed_goals.borrow_mut().insert(component.name) {
-                    if matches!(goal.mode, Gamemode::CAREER) {
-                        state.story_goals += 1;
-                    } else {
-                        state.classic_goals += 1;
-                    }
-                }
+// every story goal is beaten.  goal structs only exist once the story is loaded, so an empty list doesn't count
+fn is_story_complete(state: &State) -> bool {
+    state.story_goal_count > 0 && state.story_goals >= state.story_goal_count
+}
 
-                //if goal.is_locked {
-                //    state.locked_goals.borrow_mut().insert(component.name);
-                //}
-            }
-        }*/
+// the goal flags don't say which level a goal belongs to, but only the current level's goals get unlocked as the story goes on.
+// so the level is done when the last unlocked, unbeaten story goal is beaten without a new one unlocking alongside it
+fn is_level_complete(prev_state: &State, current_state: &State) -> bool {
+    prev_state.goals_read && current_state.level_id != 0 && current_state.level_id == prev_state.level_id &&
+        prev_state.open_story_goals > 0 && current_state.open_story_goals == 0 && current_state.story_goals > prev_state.story_goals
+}
 
-        comp = component.pnext;
-    }
+pub struct Watchers {
+    level_id: MemoryWatcher<u8>,
+    game_flags: MemoryWatcher<u8>,
+    total_classic_goals: MemoryWatcher<u8>,
+    classic_triangle_goals: MemoryWatcher<u8>,
+    run_ended: MemoryWatcher<u8>,
+    is_loading: MemoryWatcher<bool>,
+    story_flags: MemoryWatcher<u8>,
+    story_points: MemoryWatcher<u16>,
+    goal_flags: MemoryWatcher<u32>,
+}
 
-    result
+impl Watchers {
+    pub fn new(offsets: &Offsets) -> Self {
+        Self {
+            level_id: MemoryWatcher::new(&[offsets.skate as u64, 0x20, 0x630]),
+            game_flags: MemoryWatcher::new(&[offsets.skate as u64, 0x20, 0x610]),
+            total_classic_goals: MemoryWatcher::new(&[offsets.skate as u64, 0x20, 0x5EE]),
+            classic_triangle_goals: MemoryWatcher::new(&[offsets.skate as u64, 0x20, 0x5E4]),
+            run_ended: MemoryWatcher::new(&[offsets.run_ended as u64, 0x1A18, 0xC]),
+            is_loading: MemoryWatcher::new(&[offsets.loading as u64]),
+            story_flags: MemoryWatcher::new(&[offsets.skate as u64, 0x20, 0x634]),
+            story_points: MemoryWatcher::new(&[offsets.skate as u64, 0x20, 0x5d8]),
+            goal_flags: MemoryWatcher::new(&[offsets.skate as u64, 0x38c, 0x14]),
+        }
+    }
 }
 
 impl State {
-    pub fn update(process: &Process, base_addr: Address) -> Self {
-        let (_story_difficulty, classic_difficulty) = if let Ok(addr) = process.read_pointer_path::<u32>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x38c as u64, 0x14 as u64)) {
-            get_difficulties(process, addr)
-        } else {
-            (Difficulty::UNKNOWN, Difficulty::UNKNOWN)
+    pub fn update(process: &impl MemorySource, base_addr: Address, watchers: &mut Watchers) -> Self {
+        let mut result = State {
+            level_id: *watchers.level_id.update(process, base_addr).current(),
+            is_game_finished: (watchers.game_flags.update(process, base_addr).current() & 0x40) != 0,
+            total_classic_goals: *watchers.total_classic_goals.update(process, base_addr).current(),
+            classic_triangle_goals: *watchers.classic_triangle_goals.update(process, base_addr).current(),
+            is_run_ended: *watchers.run_ended.update(process, base_addr).current() != 0,
+            is_loading: *watchers.is_loading.update(process, base_addr).current(),
+            is_story_started: (watchers.story_flags.update(process, base_addr).current() & 0x1) != 0,
+            story_points: *watchers.story_points.update(process, base_addr).current(),
+
+            _story_difficulty: Difficulty::UNKNOWN,
+            classic_difficulty: Difficulty::UNKNOWN,
+            goals_read: false,
+            completed_goals: HashSet::new(),
+            story_goals: 0,
+            story_goal_count: 0,
+            open_story_goals: 0,
+            completed_classic_goals: 0,
         };
 
-        State {
-            level_id: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x20 as u64, 0x630 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            is_game_finished: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x20 as u64, 0x610 as u64)) {
-                Ok(v) => v & 0x40 != 0,
-                Err(_) => false,
-            },
-
-            total_classic_goals: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x20 as u64, 0x5EE as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            classic_triangle_goals: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x20 as u64, 0x5E4 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
-
-            is_run_ended: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x2F3624 as u64, 0x1A18 as u64, 0xC as u64)) {
-                Ok(v) => v != 0,
-                Err(_) => false,
-            },
-
-            is_loading: match process.read_pointer_path::<bool>(base_addr, asr::PointerSize::Bit32, &vec!(0x2FC49C as u64)) {
-                Ok(v) => v,
-                Err(_) => false,
-            },
-
-            is_story_started: match process.read_pointer_path::<u8>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x20 as u64, 0x634 as u64)) {
-                Ok(v) => v & 0x1 != 0,
-                Err(_) => false,
-            },
+        let goal_flags = *watchers.goal_flags.update(process, base_addr).current();
+        if goal_flags != 0 {
+            update_goal_flags(process, goal_flags, &mut result);
+        }
 
-            story_points: match process.read_pointer_path::<u16>(base_addr, asr::PointerSize::Bit32, &vec!(0x3ce478 as u64, 0x20 as u64, 0x5d8 as u64)) {
-                Ok(v) => v,
-                Err(_) => 0,
-            },
+        result
+    }
 
-            _story_difficulty,
-            classic_difficulty,
-        }
+    pub fn publish_variables(&self, variables: &mut Variables) {
+        variables.set("Level", self.level_id);
+        variables.set("Goals", self.story_goals);
+        variables.set("Classic Goals", self.total_classic_goals);
+        variables.set("Story Points", self.story_points);
     }
 }
 
@@ -220,88 +255,194 @@ enum Gamemode {
     CLASSIC,
 }
 
-pub async fn run(process: &Process, process_name: &str) {
-    asr::print_message("Attached to THUG2!");
-    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+impl TraceState for State {
+    fn write_trace(&self, w: &mut TraceWriter) {
+        w.field("level", self.level_id);
+        w.field("classic_goals", self.total_classic_goals);
+        w.field("triangle_goals", self.classic_triangle_goals);
+        w.field("run_ended", self.is_run_ended);
+        w.field("game_finished", self.is_game_finished);
+        w.field("loading", self.is_loading);
+        w.field("story_started", self.is_story_started);
+        w.field("story_points", self.story_points);
+        w.field("story_difficulty", self._story_difficulty as u8);
+        w.field("classic_difficulty", self.classic_difficulty as u8);
+        w.field("goals_read", self.goals_read);
+        w.set_field("completed", self.completed_goals.iter());
+        w.field("story_goals", self.story_goals);
+        w.field("story_goal_count", self.story_goal_count);
+        w.field("open_story_goals", self.open_story_goals);
+        w.field("completed_classic_goals", self.completed_classic_goals);
+    }
+
+    fn read_trace(r: &TraceReader) -> Option<Self> {
+        Some(Self {
+            level_id: r.field("level")?,
+            total_classic_goals: r.field("classic_goals")?,
+            classic_triangle_goals: r.field("triangle_goals")?,
+            is_run_ended: r.field("run_ended")?,
+            is_game_finished: r.field("game_finished")?,
+            is_loading: r.field("loading")?,
+            is_story_started: r.field("story_started")?,
+            story_points: r.field("story_points")?,
+            _story_difficulty: Difficulty::from_id(r.field("story_difficulty")?),
+            classic_difficulty: Difficulty::from_id(r.field("classic_difficulty")?),
+            goals_read: r.field("goals_read")?,
+            completed_goals: r.set_field("completed")?,
+            story_goals: r.field("story_goals")?,
+            story_goal_count: r.field("story_goal_count")?,
+            open_story_goals: r.field("open_story_goals")?,
+            completed_classic_goals: r.field("completed_classic_goals")?,
+        })
+    }
+}
+
+// one split for each goal completed since the last tick.  goals that already split this run are remembered,
+// so a goal that drops out of a bad read and comes back doesn't split again
+fn split_on_goals(split_goals: &mut HashSet<u32>, timer: &mut impl Timer, settings: &Settings, prev_state: &State, current_state: &State) {
+    if !settings.thug2_split_goal || !prev_state.goals_read {
+        return;
+    }
 
-    let base_addr = process.get_module_address(process_name).unwrap();
+    for goal in current_state.completed_goals.difference(&prev_state.completed_goals) {
+        if split_goals.insert(*goal) {
+            timer.split();
+            info!("Completed {}; splitting timer...", checksum::describe(*goal));
+        }
+    }
+}
 
-    let mut prev_state = State::update(process, base_addr);
+pub struct Splitter {
+    prev_state: State,
+    continuing_series: bool,
+    mode: Gamemode,
+    split_goals: HashSet<u32>,
+    split_levels: HashSet<u8>,
+}
 
-    let mut mode = Gamemode::NONE;
+impl GameSplitter for Splitter {
+    type State = State;
 
-    loop {
-        // update vars
-        let current_state = State::update(process, base_addr);
+    fn new(initial_state: State, continuing_series: bool) -> Self {
+        Self {
+            prev_state: initial_state,
+            continuing_series,
+            // series runs go straight into story mode since the timer is already running
+            mode: if continuing_series {
+                Gamemode::CAREER
+            } else {
+                Gamemode::NONE
+            },
+            split_goals: HashSet::new(),
+            split_levels: HashSet::new(),
+        }
+    }
+
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, mut current_state: State) {
+        let prev_state = &self.prev_state;
+
+        // the goal flags can't always be read (e.g. while loading), so keep the last progress instead of losing every goal
+        if !current_state.goals_read && prev_state.goals_read {
+            current_state.goals_read = true;
+            current_state.completed_goals = prev_state.completed_goals.clone();
+            current_state.story_goals = prev_state.story_goals;
+            current_state.story_goal_count = prev_state.story_goal_count;
+            current_state.open_story_goals = prev_state.open_story_goals;
+            current_state.completed_classic_goals = prev_state.completed_classic_goals;
+        }
 
         if current_state.story_points != prev_state.story_points {
-            asr::print_message(format!("Story points changed to {}", current_state.story_points).as_str());
+            debug!("Story points changed to {}", current_state.story_points);
         }
 
         // pause game time when loading, resume when done
-        if current_state.is_loading && !prev_state.is_loading {
-            asr::timer::pause_game_time();
-            asr::print_message(format!("Starting Load...").as_str());
-        } else if !current_state.is_loading && prev_state.is_loading {
-            asr::timer::resume_game_time();
-            asr::print_message(format!("Done Loading").as_str());
+        let is_loading = Watcher::between(prev_state.is_loading, current_state.is_loading);
+        if is_loading.changed_to(&true) {
+            timer.pause_game_time();
+            debug!("Starting Load...");
+        } else if is_loading.changed_to(&false) {
+            timer.resume_game_time();
+            debug!("Done Loading");
         }
 
-        match asr::timer::state() {
+        let level_id = Watcher::between(prev_state.level_id, current_state.level_id);
+
+        match timer.state() {
             TimerState::NotRunning => {
+                // a series run is over once the timer stops, so the next run can auto-reset again
+                self.continuing_series = false;
+
+                self.split_goals.clear();
+                self.split_levels.clear();
+
                 // story
-                if current_state.level_id == 1 && prev_state.level_id == 9 {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer for story mode...").as_str());
-                    mode = Gamemode::CAREER;
+                if settings.thug2_start_story && *level_id.old() == 9 && level_id.changed_to(&1) {
+                    timer.start();
+                    info!("Starting timer for story mode...");
+                    self.mode = Gamemode::CAREER;
                 }
                 // classic
-                if current_state.level_id == 2 && prev_state.level_id == 0 && current_state.total_classic_goals == 0 {
-                    asr::timer::start();
-                    asr::print_message(format!("Starting timer for classic mode...").as_str());
-                    mode = Gamemode::CLASSIC;
+                if settings.thug2_start_classic && *level_id.old() == 0 && level_id.changed_to(&2) && current_state.total_classic_goals == 0 {
+                    timer.start();
+                    info!("Starting timer for classic mode...");
+                    self.mode = Gamemode::CLASSIC;
                 }
             },
             TimerState::Paused | TimerState::Running => {
-                match mode {
+                match self.mode {
                     Gamemode::NONE => {},
                     Gamemode::CAREER => {
-                        if current_state.level_id != 0 && current_state.level_id != prev_state.level_id {
-                            asr::timer::split();
-                            asr::print_message(format!("Changed level; splitting timer...").as_str());
+                        if settings.thug2_split_level && current_state.level_id != 0 && level_id.changed() {
+                            timer.split();
+                            info!("Changed level; splitting timer...");
                         } 
                         
-                        if current_state.is_game_finished && !prev_state.is_game_finished {
-                            asr::timer::split();
-                            asr::print_message(format!("Final cutscene; splitting timer...").as_str());
+                        split_on_goals(&mut self.split_goals, timer, settings, prev_state, &current_state);
+
+                        // once per level, so replaying a level's goals later in the run doesn't split again
+                        if settings.thug2_split_level_complete && is_level_complete(prev_state, &current_state) && self.split_levels.insert(current_state.level_id) {
+                            timer.split();
+                            info!("All goals on level {} completed; splitting timer...", current_state.level_id);
+                        }
+
+                        if settings.thug2_split_story_complete && is_story_complete(&current_state) && !is_story_complete(prev_state) {
+                            timer.split();
+                            info!("All story goals completed; splitting timer...");
+                        }
+
+                        if settings.thug2_split_final && Watcher::between(prev_state.is_game_finished, current_state.is_game_finished).changed_to(&true) {
+                            timer.split();
+                            info!("Final cutscene; splitting timer...");
                         }
         
                         // reset when story start flag is unset
-                        if current_state.level_id == 0 && !current_state.is_story_started && current_state.story_points == 0 {
-                            asr::timer::reset();
-                            asr::print_message(format!("Resetting timer...").as_str());
-                            mode = Gamemode::NONE;
+                        if settings.thug2_reset && !self.continuing_series && current_state.level_id == 0 && !current_state.is_story_started && current_state.story_points == 0 {
+                            timer.reset();
+                            info!("Resetting timer...");
+                            self.mode = Gamemode::NONE;
                         }
                     },
                     Gamemode::CLASSIC => {
-                        if current_state.level_id != 0 && current_state.level_id != prev_state.level_id {
-                            asr::timer::split();
-                            asr::print_message(format!("Changed level; splitting timer...").as_str());
+                        if settings.thug2_split_level && current_state.level_id != 0 && level_id.changed() {
+                            timer.split();
+                            info!("Changed level; splitting timer...");
                         } 
 
-                        if current_state.is_run_ended && !prev_state.is_run_ended && 
+                        split_on_goals(&mut self.split_goals, timer, settings, prev_state, &current_state);
+
+                        if settings.thug2_split_classic_end && Watcher::between(prev_state.is_run_ended, current_state.is_run_ended).changed_to(&true) && 
                             ((current_state.total_classic_goals < 120 && current_state.classic_difficulty == Difficulty::NORMAL && current_state.classic_triangle_goals >= 6) || 
                             (current_state.total_classic_goals < 120 && current_state.classic_difficulty == Difficulty::SICK && current_state.classic_triangle_goals >= 8) || 
                             (current_state.total_classic_goals == 140)) {
-                            asr::timer::split();
-                            asr::print_message(format!("End of classic mode; splitting timer...").as_str());
+                            timer.split();
+                            info!("End of classic mode; splitting timer...");
                         }
 
                         // reset when on 0 goals are completed
-                        if current_state.level_id == 0 && prev_state.total_classic_goals == 0 {
-                            asr::timer::reset();
-                            asr::print_message(format!("Resetting timer...").as_str());
-                            mode = Gamemode::NONE;
+                        if settings.thug2_reset && !self.continuing_series && current_state.level_id == 0 && prev_state.total_classic_goals == 0 {
+                            timer.reset();
+                            info!("Resetting timer...");
+                            self.mode = Gamemode::NONE;
                         }
                     },
                 }
@@ -312,8 +453,192 @@ pub async fn run(process: &Process, process_name: &str) {
             },
         }
 
-        prev_state = current_state;
+        self.prev_state = current_state;
+    }
+}
+
+pub async fn run(process: &Process, process_name: &str, settings: &mut Settings, continuing_series: bool) {
+    info!("Attached to THUG2!");
+    asr::set_tick_rate(120.0);  // just in case, explicitly set the tick rate to 120
+
+    let (base_addr, module_size) = attach::module_range(process, process_name).await;
+    let offsets = Offsets::get(process, base_addr, module_size);
+
+    let mut watchers = Watchers::new(&offsets);
+    let mut splitter = Splitter::new(State::update(process, base_addr, &mut watchers), continuing_series);
+    let mut variables = Variables::new();
+    let mut recorder = Recorder::new("THUG2");
+    let mut researcher = Researcher::new(asr::PointerSize::Bit32);
+
+    loop {
+        settings.refresh();
+
+        // update vars
+        let current_state = State::update(process, base_addr, &mut watchers);
+        recorder.record(settings, &current_state);
+        current_state.publish_variables(&mut variables);
+        researcher.update(process, base_addr, settings, &mut variables);
+
+        splitter.update(&mut AsrTimer, settings, current_state);
 
         asr::future::next_tick().await;
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use asr::PointerSize;
+    use bytemuck::NoUninit;
+
+    use super::*;
+    use crate::{memory::MockMemory, trace::{ReplayTimer, TimerEvent::{self, *}}};
+
+    const BASE: u64 = 0x400000;
+    const FLAGS: u32 = 0x20000000;
+
+    // component headers, with the type in the second byte shifted left by one
+    const INTEGER: u32 = 0x1 << 9;
+    const STRUCT: u32 = 0xa << 9;
+    const CHECKSUM: u32 = 0xd << 9;
+
+    // the game's memory, plus the autosplitter loop running against it
+    struct Game {
+        offsets: Offsets,
+        memory: MockMemory,
+        watchers: Watchers,
+        splitter: Splitter,
+        timer: ReplayTimer,
+        settings: Settings,
+    }
+
+    impl Game {
+        fn new() -> Self {
+            let offsets = OFFSETS;
+            let memory = MockMemory::new();
+            let mut watchers = Watchers::new(&offsets);
+            let splitter = Splitter::new(State::update(&memory, Address::new(BASE), &mut watchers), false);
+
+            Self { offsets, memory, watchers, splitter, timer: ReplayTimer::new(None), settings: Settings::default() }
+        }
+
+        fn set<T: NoUninit>(&mut self, path: &[u64], value: T) {
+            self.memory.write_pointer_path(Address::new(BASE), PointerSize::Bit32, path, value);
+        }
+
+        // the skater's career, where the level, story progress and classic goals are kept
+        fn set_career<T: NoUninit>(&mut self, offset: u64, value: T) {
+            self.set(&[self.offsets.skate as u64, 0x20, offset], value);
+        }
+
+        // a script struct at addr, with its components laid out right after it
+        fn write_struct(&mut self, addr: u32, components: &[(u32, u32, u32)]) {
+            self.memory.write(addr + 4, if components.is_empty() { 0 } else { addr + 0x10 });
+            for (i, (header, name, data)) in components.iter().enumerate() {
+                let comp = addr + 0x10 + i as u32 * 0x10;
+                let next = if i + 1 < components.len() { comp + 0x10 } else { 0 };
+                self.memory.write(comp, [*header, *name, *data, next]);
+            }
+        }
+
+        // the goal flags, with one struct per (mode, beaten, locked) goal.  beaten goals have unk2 set, like the game does
+        fn set_goals(&mut self, goals: &[(u32, bool, bool)]) {
+            self.set(&[self.offsets.skate as u64, 0x38c, 0x14], FLAGS);
+
+            let mut components = Vec::new();
+            for (i, (mode, beaten, locked)) in goals.iter().enumerate() {
+                let goal = FLAGS + 0x1000 * (i as u32 + 1);
+                self.write_struct(goal, &[
+                    (CHECKSUM, 0, *mode),
+                    (INTEGER, checksum!("hasBeaten"), *beaten as u32),
+                    (INTEGER, checksum!("isLocked"), *locked as u32),
+                    (INTEGER, 0x290a18e3, *beaten as u32),
+                ]);
+                components.push((STRUCT, 0x100 + i as u32, goal));
+            }
+            self.write_struct(FLAGS, &components);
+        }
+
+        // one tick of the autosplitter loop, returning what the timer was told to do
+        fn tick(&mut self) -> Vec<TimerEvent> {
+            let events = self.timer.events.len();
+            let state = State::update(&self.memory, Address::new(BASE), &mut self.watchers);
+            self.splitter.update(&mut self.timer, &self.settings, state);
+
+            self.timer.events[events..].iter().map(|(_, e)| *e).collect()
+        }
+    }
+
+    #[test]
+    fn starts_story_after_the_intro_and_splits_on_the_ending() {
+        let mut game = Game::new();
+        game.set_career(0x630, 9u8);
+        assert!(game.tick().is_empty());
+
+        game.set_career(0x630, 1u8);
+        game.set_career(0x634, 0x1u8);
+        assert_eq!(game.tick(), [Start]);
+
+        game.set_career(0x630, 3u8);
+        assert_eq!(game.tick(), [Split]);
+
+        game.set_career(0x610, 0x40u8);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
+    }
+
+    #[test]
+    fn classic_splits_when_the_last_run_ends() {
+        let mut game = Game::new();
+        game.set_career(0x630, 2u8);
+        assert_eq!(game.tick(), [Start]);
+
+        game.set_career(0x5ee, 140u8);
+        assert!(game.tick().is_empty());
+
+        game.set(&[game.offsets.run_ended as u64, 0x1a18, 0xc], 1u8);
+        assert_eq!(game.tick(), [Split]);
+    }
+
+    #[test]
+    fn pauses_game_time_while_loading() {
+        let mut game = Game::new();
+        game.set(&[game.offsets.loading as u64], 1u8);
+        assert_eq!(game.tick(), [PauseGameTime]);
+
+        game.set(&[game.offsets.loading as u64], 0u8);
+        assert_eq!(game.tick(), [ResumeGameTime]);
+    }
+
+    #[test]
+    fn splits_when_a_levels_unlocked_story_goals_are_done() {
+        let mut game = Game::new();
+        game.settings.thug2_split_level_complete = true;
+        game.set_career(0x630, 9u8);
+        game.set_goals(&[(checksum!("career"), false, false), (checksum!("career"), false, false), (checksum!("career"), false, true), (checksum!("classic"), false, false)]);
+        assert!(game.tick().is_empty());
+
+        game.set_career(0x630, 1u8);
+        game.set_career(0x634, 0x1u8);
+        assert_eq!(game.tick(), [Start]);
+
+        game.set_goals(&[(checksum!("career"), true, false), (checksum!("career"), false, false), (checksum!("career"), false, true), (checksum!("classic"), false, false)]);
+        assert!(game.tick().is_empty());
+
+        game.set_goals(&[(checksum!("career"), true, false), (checksum!("career"), true, false), (checksum!("career"), false, true), (checksum!("classic"), true, false)]);
+        assert_eq!(game.tick(), [Split]);
+        assert!(game.tick().is_empty());
+
+        let state = State::update(&game.memory, Address::new(BASE), &mut game.watchers);
+        assert_eq!((state.story_goals, state.story_goal_count, state.completed_classic_goals), (2, 3, 1));
+    }
+
+    #[test]
+    fn goals_without_unk2_or_a_mode_dont_count() {
+        let mut game = Game::new();
+        game.set_goals(&[(checksum!("career"), true, false), (checksum!("net"), true, false)]);
+        game.memory.write(FLAGS + 0x1000 + 0x40, [INTEGER, 0x290a18e3, 0, 0]);
+
+        let state = State::update(&game.memory, Address::new(BASE), &mut game.watchers);
+        assert_eq!((state.story_goals, state.story_goal_count, state.completed_classic_goals), (0, 1, 0));
+        assert!(state.completed_goals.is_empty());
+    }
+}
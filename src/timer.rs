@@ -0,0 +1,83 @@
+// the timer the game logic drives.  normally this is livesplit, but a trace replay can stand in for it
+
+use asr::{time::Duration, timer::TimerState};
+
+use crate::settings::Settings;
+
+pub trait Timer {
+    fn state(&self) -> TimerState;
+    fn start(&mut self);
+    fn split(&mut self);
+    fn reset(&mut self);
+    fn pause_game_time(&mut self);
+    fn resume_game_time(&mut self);
+    fn set_game_time(&mut self, time: Duration);
+
+    fn is_active(&self) -> bool {
+        matches!(self.state(), TimerState::Running) || matches!(self.state(), TimerState::Paused)
+    }
+}
+
+// called when a game is detected.  if a series run is in progress, the new game picks up the run where the last one left off.
+// the replay tool goes through this and end_game too, so series runs replay the same way they play out live
+pub fn begin_game(timer: &mut impl Timer, settings: &Settings) -> bool {
+    let continuing_series = settings.series_mode && timer.is_active();
+
+    if continuing_series {
+        info!("Continuing series run...");
+
+        if settings.split_on_game_change {
+            timer.split();
+            info!("Changed games; splitting timer...");
+        }
+
+        timer.resume_game_time();
+    }
+
+    continuing_series
+}
+
+// called when a game closes
+pub fn end_game(timer: &mut impl Timer, settings: &Settings) {
+    if timer.is_active() {
+        if settings.series_mode {
+            // don't count the time spent between games
+            timer.pause_game_time();
+            info!("Series run in progress; waiting for next game...");
+        } else {
+            timer.reset();
+        }
+    }
+}
+
+pub struct AsrTimer;
+
+impl Timer for AsrTimer {
+    fn state(&self) -> TimerState {
+        asr::timer::state()
+    }
+
+    fn start(&mut self) {
+        asr::timer::start();
+    }
+
+    fn split(&mut self) {
+        asr::timer::split();
+    }
+
+    fn reset(&mut self) {
+        asr::timer::reset();
+    }
+
+    fn pause_game_time(&mut self) {
+        asr::timer::pause_game_time();
+    }
+
+    fn resume_game_time(&mut self) {
+        asr::timer::resume_game_time();
+    }
+
+    fn set_game_time(&mut self, time: Duration) {
+        asr::timer::set_game_time(time);
+    }
+}
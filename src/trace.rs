@@ -0,0 +1,352 @@
+// per-tick game state traces.  when recording is enabled, each game's state is logged whenever it changes,
+// and a trace can be fed back through the same start/split/reset logic to see what the timer would have done
+
+use std::{collections::{HashMap, HashSet}, fmt::{Display, Write}, str::FromStr};
+
+use asr::{time::Duration, timer::TimerState};
+
+use crate::{settings::Settings, timer::Timer};
+
+const TRACE_PREFIX: &str = "TRACE";
+
+// game state that can be written to and read back from a trace
+pub trait TraceState: Sized {
+    fn write_trace(&self, w: &mut TraceWriter);
+    fn read_trace(r: &TraceReader) -> Option<Self>;
+}
+
+// the start/split/reset logic for a game, fed one state per tick
+pub trait GameSplitter {
+    type State: TraceState;
+
+    fn new(initial_state: Self::State, continuing_series: bool) -> Self;
+    fn update(&mut self, timer: &mut impl Timer, settings: &Settings, current_state: Self::State);
+}
+
+#[derive(Default)]
+pub struct TraceWriter {
+    line: String,
+}
+
+impl TraceWriter {
+    pub fn field(&mut self, name: &str, value: impl Display) {
+        let _ = write!(self.line, " {}={}", name, value);
+    }
+
+    // sets of checksums are written as a comma separated list of hex values
+    pub fn set_field<'a>(&mut self, name: &str, values: impl IntoIterator<Item = &'a u32>) {
+        let mut values = values.into_iter().map(|v| format!("{:08x}", v)).collect::<Vec<_>>();
+        values.sort();
+
+        self.field(name, values.join(","));
+    }
+}
+
+pub struct TraceReader<'a> {
+    fields: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> TraceReader<'a> {
+    fn new(fields: &'a str) -> Self {
+        Self {
+            fields: fields.split_whitespace().filter_map(|field| field.split_once('=')).collect(),
+        }
+    }
+
+    pub fn field<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.fields.get(name)?.parse().ok()
+    }
+
+    pub fn set_field(&self, name: &str) -> Option<HashSet<u32>> {
+        let value = self.fields.get(name)?;
+
+        if value.is_empty() {
+            return Some(HashSet::new());
+        }
+
+        value.split(',').map(|v| u32::from_str_radix(v, 16).ok()).collect()
+    }
+}
+
+// logs a game's state whenever it differs from the last tick
+pub struct Recorder {
+    game: &'static str,
+    tick: u64,
+    last: String,
+}
+
+impl Recorder {
+    pub fn new(game: &'static str) -> Self {
+        Self {
+            game,
+            tick: 0,
+            last: String::new(),
+        }
+    }
+
+    pub fn record(&mut self, settings: &Settings, state: &impl TraceState) {
+        self.tick += 1;
+
+        if !settings.record_trace {
+            // make sure the full state gets logged if recording is turned on later
+            self.last.clear();
+            return;
+        }
+
+        let mut w = TraceWriter::default();
+        state.write_trace(&mut w);
+
+        if w.line != self.last {
+            crate::print_message(&format!("{} {} {}{}", TRACE_PREFIX, self.game, self.tick, w.line));
+            self.last = w.line;
+        }
+    }
+}
+
+pub struct TraceRecord<'a> {
+    pub game: &'a str,
+    pub tick: u64,
+    fields: &'a str,
+}
+
+impl<'a> TraceRecord<'a> {
+    // trace lines may come from a log with its own prefix (timestamps, etc.), so look for the marker anywhere in the line
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let (_, record) = line.split_once(&format!("{} ", TRACE_PREFIX))?;
+        let mut parts = record.splitn(3, ' ');
+
+        let game = parts.next()?;
+        let tick = parts.next()?.parse().ok()?;
+        let fields = parts.next().unwrap_or("");
+
+        Some(Self {
+            game,
+            tick,
+            fields,
+        })
+    }
+
+    pub fn read<S: TraceState>(&self) -> Option<S> {
+        S::read_trace(&TraceReader::new(self.fields))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+    Start,
+    Split,
+    Reset,
+    PauseGameTime,
+    ResumeGameTime,
+    SetGameTime(Duration),
+}
+
+// stands in for livesplit during a replay, keeping a timeline of everything the game logic did
+pub struct ReplayTimer {
+    state: TimerState,
+    segments: Option<usize>,
+    splits: usize,
+    pub tick: u64,
+    pub events: Vec<(u64, TimerEvent)>,
+}
+
+impl ReplayTimer {
+    // with a segment count, the timer ends on the last split like livesplit would
+    pub fn new(segments: Option<usize>) -> Self {
+        Self {
+            state: TimerState::NotRunning,
+            segments,
+            splits: 0,
+            tick: 0,
+            events: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, event: TimerEvent) {
+        self.events.push((self.tick, event));
+    }
+}
+
+impl Timer for ReplayTimer {
+    fn state(&self) -> TimerState {
+        self.state
+    }
+
+    fn start(&mut self) {
+        if matches!(self.state, TimerState::NotRunning) {
+            self.state = TimerState::Running;
+            self.splits = 0;
+            self.push(TimerEvent::Start);
+        }
+    }
+
+    fn split(&mut self) {
+        if self.is_active() {
+            self.splits += 1;
+            self.push(TimerEvent::Split);
+
+            if self.segments.is_some_and(|segments| self.splits >= segments) {
+                self.state = TimerState::Ended;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        if !matches!(self.state, TimerState::NotRunning) {
+            self.state = TimerState::NotRunning;
+            self.push(TimerEvent::Reset);
+        }
+    }
+
+    fn pause_game_time(&mut self) {
+        self.push(TimerEvent::PauseGameTime);
+    }
+
+    fn resume_game_time(&mut self) {
+        self.push(TimerEvent::ResumeGameTime);
+    }
+
+    fn set_game_time(&mut self, time: Duration) {
+        self.push(TimerEvent::SetGameTime(time));
+    }
+}
+
+// feeds one game's records through its splitter.  ticks between records repeat the last recorded state
+fn replay_game<S: GameSplitter>(records: &[TraceRecord], timer: &mut ReplayTimer, settings: &Settings, continuing_series: bool) -> Result<(), String> {
+    let read = |record: &TraceRecord| record.read::<S::State>().ok_or_else(|| format!("Invalid {} record at tick {}", record.game, record.tick));
+
+    let Some(first) = records.first() else {
+        return Ok(());
+    };
+
+    let mut splitter = S::new(read(first)?, continuing_series);
+
+    for (i, record) in records.iter().enumerate() {
+        let next_tick = match records.get(i + 1) {
+            Some(next) => next.tick,
+            None => record.tick + 1,
+        };
+
+        for tick in record.tick..next_tick {
+            timer.tick = tick;
+            splitter.update(timer, settings, read(record)?);
+        }
+    }
+
+    Ok(())
+}
+
+// replays every game in a trace in order.  a trace with several games is treated like a series run
+pub fn replay(records: &[TraceRecord], timer: &mut ReplayTimer, settings: &Settings) -> Result<(), String> {
+    let mut start = 0;
+
+    while start < records.len() {
+        let game = records[start].game;
+        let end = records[start..].iter().position(|r| r.game != game).map_or(records.len(), |i| start + i);
+        if start > 0 {
+            crate::timer::end_game(timer, settings);
+        }
+
+        crate::log::set_prefix(game);
+        let continuing_series = crate::timer::begin_game(timer, settings);
+
+        let game_records = &records[start..end];
+        match game {
+            "THPS2" => replay_game::<crate::thps2::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THPS3" => replay_game::<crate::thps3::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THPS4" => replay_game::<crate::thps4::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THUG1" => replay_game::<crate::thug1::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THUG2" => replay_game::<crate::thug2::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THAW" => replay_game::<crate::thaw::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THPS12" => replay_game::<crate::thps12::Splitter>(game_records, timer, settings, continuing_series)?,
+            "THPS34" => replay_game::<crate::thps34::Splitter>(game_records, timer, settings, continuing_series)?,
+            "MHPB" => replay_game::<crate::mhpb::Splitter>(game_records, timer, settings, continuing_series)?,
+            _ => return Err(format!("Unknown game in trace: {}", game)),
+        }
+
+        start = end;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TimerEvent::*;
+
+    #[test]
+    fn series_adds_thps2_runs_on_top_of_the_last_game() {
+        let trace = [
+            "TRACE THPS4 1 level=0 cash=0 pro_points=0 pro_goals=0 loading=false",
+            "TRACE THPS4 2 level=1 cash=0 pro_points=0 pro_goals=0 loading=false",
+            "TRACE THPS2 1 timer_running=false timer_end=7200 seconds=0 level=0 mode=1 screen=6 golds=0 medals=0 goals=0 no_progress=true",
+            "TRACE THPS2 2 timer_running=true timer_end=7200 seconds=0 level=0 mode=1 screen=6 golds=0 medals=0 goals=0 no_progress=true",
+            "TRACE THPS2 3 timer_running=true timer_end=7200 seconds=10 level=0 mode=1 screen=6 golds=0 medals=0 goals=0 no_progress=true",
+            "TRACE THPS2 4 timer_running=false timer_end=7200 seconds=10 level=0 mode=1 screen=6 golds=0 medals=0 goals=0 no_progress=true",
+        ];
+        let records: Vec<_> = trace.iter().filter_map(|line| TraceRecord::parse(line)).collect();
+        let settings = Settings { series_mode: true, ..Settings::default() };
+
+        let mut timer = ReplayTimer::new(None);
+        replay(&records, &mut timer, &settings).unwrap();
+
+        // game time is only ever paused and resumed, so the time from THPS4 is kept
+        let events: Vec<_> = timer.events.iter().map(|(_, e)| *e).collect();
+        assert_eq!(events, [
+            Start,
+            PauseGameTime, ResumeGameTime,  // between games
+            PauseGameTime,  // the run clock hasn't started yet
+            ResumeGameTime,
+            PauseGameTime,
+        ]);
+    }
+
+    #[test]
+    fn series_runs_dont_split_on_the_level_they_attach_in() {
+        let trace = [
+            "TRACE THPS4 1 level=0 cash=0 pro_points=0 pro_goals=0 loading=false",
+            "TRACE THPS4 2 level=1 cash=0 pro_points=0 pro_goals=0 loading=false",
+            "TRACE THPS12 1 level=Warehouse goals=0 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=0 thps1_skaters=0 thps2_skaters=0 run_time=0 run_clock_running=false mode=2 running=true loading=false",
+            "TRACE THPS12 2 level=Warehouse goals=1 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=1 thps1_skaters=0 thps2_skaters=0 run_time=0 run_clock_running=false mode=2 running=true loading=false",
+            "TRACE THPS34 1 level=Foundry goals=0 thps3_prediction=0 thps4_prediction=0 thps3_stars=0 thps4_stars=0 total_goals=0 thps3_skaters=0 thps4_skaters=0 run_time=0 run_clock_running=false mode=2 running=true loading=false",
+            "TRACE THPS34 2 level=Foundry goals=1 thps3_prediction=0 thps4_prediction=0 thps3_stars=0 thps4_stars=0 total_goals=1 thps3_skaters=0 thps4_skaters=0 run_time=0 run_clock_running=false mode=2 running=true loading=false",
+        ];
+        let records: Vec<_> = trace.iter().filter_map(|line| TraceRecord::parse(line)).collect();
+        let settings = Settings { series_mode: true, ..Settings::default() };
+
+        let mut timer = ReplayTimer::new(None);
+        replay(&records, &mut timer, &settings).unwrap();
+
+        let events: Vec<_> = timer.events.iter().map(|(_, e)| *e).collect();
+        assert_eq!(events, [
+            Start,
+            PauseGameTime, ResumeGameTime,  // THPS4 to THPS1+2
+            PauseGameTime, ResumeGameTime,  // THPS1+2 to THPS3+4
+        ]);
+    }
+
+    #[test]
+    fn sum_of_runs_adds_up_the_run_clock() {
+        let trace = [
+            "TRACE THPS12 1 level=FrontEnd goals=0 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=0 thps1_skaters=0 thps2_skaters=0 run_time=0 run_clock_running=false mode=2 running=false loading=false",
+            "TRACE THPS12 2 level=Warehouse goals=0 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=0 thps1_skaters=0 thps2_skaters=0 run_time=0 run_clock_running=true mode=2 running=true loading=false",
+            "TRACE THPS12 3 level=Warehouse goals=0 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=0 thps1_skaters=0 thps2_skaters=0 run_time=30 run_clock_running=true mode=2 running=true loading=false",
+            "TRACE THPS12 4 level=Warehouse goals=0 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=0 thps1_skaters=0 thps2_skaters=0 run_time=0 run_clock_running=false mode=2 running=false loading=false",
+            "TRACE THPS12 5 level=Warehouse goals=0 roswell=false bullring=false golds=0 thps1_complete=false thps2_complete=false total_goals=0 thps1_skaters=0 thps2_skaters=0 run_time=5 run_clock_running=true mode=2 running=true loading=false",
+        ];
+        let records: Vec<_> = trace.iter().filter_map(|line| TraceRecord::parse(line)).collect();
+        let settings = Settings { thps12_timing: crate::settings::AlcatrazTiming::SumOfRuns, ..Settings::default() };
+
+        let mut timer = ReplayTimer::new(None);
+        replay(&records, &mut timer, &settings).unwrap();
+
+        let events: Vec<_> = timer.events.iter().map(|(_, e)| *e).collect();
+        assert_eq!(events, [
+            Start,
+            PauseGameTime,  // game time only comes from the run clock
+            SetGameTime(Duration::seconds(30)),
+            SetGameTime(Duration::seconds(35)),
+        ]);
+    }
+}
@@ -0,0 +1,42 @@
+// game stats published as livesplit custom variables so layouts can show them.  games share a name when the value means the same thing
+// (Level, Goals, Medals, Gold Medals, Pro Points, Pro Goals, Cash, Chapter, ...)
+
+use std::{collections::HashMap, fmt::Display};
+
+pub struct Variables {
+    values: HashMap<&'static str, String>,
+    changes: Vec<String>,   // described as "Name: old -> new", for research mode
+}
+
+impl Variables {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    // only sends the value to livesplit when it changed
+    pub fn set(&mut self, name: &'static str, value: impl Display) {
+        let value = value.to_string();
+
+        if self.values.get(name) != Some(&value) {
+            asr::timer::set_variable(name, &value);
+
+            if let Some(old) = self.values.insert(name, value.clone()) {
+                self.changes.push(format!("{}: {} -> {}", name, old, value));
+            }
+        }
+    }
+
+    // the values that changed since this was last called.  the first value published for each variable doesn't count as a change
+    pub fn take_changes(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.changes)
+    }
+}
+
+impl Default for Variables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
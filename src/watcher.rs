@@ -0,0 +1,130 @@
+// values that are checked every tick, remembering what they were on the last one.  a watcher can either drop back to the default when a read
+// fails (the same as the old `Err(_) => 0` reads), or hold its last good value through glitches like the level name reading as "None" mid-load
+
+use std::ops::Deref;
+
+use asr::Address;
+use bytemuck::CheckedBitPattern;
+
+use crate::memory::MemorySource;
+
+pub struct Watcher<T> {
+    old: T,
+    current: T,
+    hold: bool,
+}
+
+impl<T: Clone + Default + PartialEq> Watcher<T> {
+    // goes back to the default value whenever there's nothing to read
+    pub fn new() -> Self {
+        Self {
+            old: T::default(),
+            current: T::default(),
+            hold: false,
+        }
+    }
+
+    // keeps the last good value whenever there's nothing to read
+    pub fn holding() -> Self {
+        Self {
+            hold: true,
+            ..Self::new()
+        }
+    }
+
+    // keeps the last good value like `holding`, but starts from a value that was already read
+    pub fn holding_from(value: T) -> Self {
+        Self {
+            old: value.clone(),
+            current: value,
+            hold: true,
+        }
+    }
+
+    // one field of the previous and current game states, so splitters can use the same checks
+    pub fn between(old: T, current: T) -> Self {
+        Self {
+            old,
+            current,
+            hold: false,
+        }
+    }
+
+    // moves the current value to old, then takes the new one.  None means the read failed or gave junk
+    pub fn update(&mut self, value: Option<T>) -> &Self {
+        self.old = self.current.clone();
+
+        match value {
+            Some(v) => self.current = v,
+            None if !self.hold => self.current = T::default(),
+            None => {},
+        }
+
+        self
+    }
+
+    pub fn old(&self) -> &T {
+        &self.old
+    }
+
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    pub fn changed(&self) -> bool {
+        self.old != self.current
+    }
+
+    // became this value on the latest update
+    pub fn changed_to(&self, value: &T) -> bool {
+        self.changed() && self.current == *value
+    }
+}
+
+impl<T: Clone + Default + PartialOrd> Watcher<T> {
+    // went up to this value on the latest update
+    pub fn rose_to(&self, value: &T) -> bool {
+        self.old < *value && self.current == *value
+    }
+}
+
+impl<T: Clone + Default + PartialEq> Default for Watcher<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// a watcher for a value at the end of a pointer path.  the neversoft games are all 32 bit, so the pointers are too
+pub struct MemoryWatcher<T> {
+    path: Vec<u64>,
+    watcher: Watcher<T>,
+}
+
+impl<T: CheckedBitPattern + Clone + Default + PartialEq> MemoryWatcher<T> {
+    pub fn new(path: &[u64]) -> Self {
+        Self {
+            path: path.to_vec(),
+            watcher: Watcher::new(),
+        }
+    }
+
+    pub fn holding(path: &[u64]) -> Self {
+        Self {
+            path: path.to_vec(),
+            watcher: Watcher::holding(),
+        }
+    }
+
+    pub fn update(&mut self, process: &impl MemorySource, base_addr: Address) -> &Watcher<T> {
+        let value = process.read_pointer_path::<T>(base_addr, asr::PointerSize::Bit32, &self.path).ok();
+        self.watcher.update(value)
+    }
+}
+
+impl<T> Deref for MemoryWatcher<T> {
+    type Target = Watcher<T>;
+
+    fn deref(&self) -> &Watcher<T> {
+        &self.watcher
+    }
+}